@@ -34,6 +34,13 @@ pub trait AclStorage: Send + Sync {
 	fn check(&self, public: &Public, document: &ServerKeyId) -> Result<bool, Error>;
 }
 
+/// Node-level handle of ACL storage, which could be swapped without restarting the node.
+/// Sessions are capturing current backend when created, so swapping only affects sessions created after the swap.
+pub struct AclStorageHandle {
+	/// Current ACL storage backend.
+	backend: RwLock<Arc<AclStorage>>,
+}
+
 /// On-chain ACL storage implementation.
 pub struct OnChainAclStorage {
 	/// Cached on-chain contract.
@@ -56,6 +63,25 @@ pub struct DummyAclStorage {
 	prohibited: RwLock<HashMap<Public, HashSet<ServerKeyId>>>,
 }
 
+impl AclStorageHandle {
+	/// Create new handle with given backend.
+	pub fn new(backend: Arc<AclStorage>) -> Self {
+		AclStorageHandle {
+			backend: RwLock::new(backend),
+		}
+	}
+
+	/// Get current ACL storage backend.
+	pub fn current(&self) -> Arc<AclStorage> {
+		self.backend.read().clone()
+	}
+
+	/// Atomically replace ACL storage backend. Returns previous backend.
+	pub fn swap(&self, backend: Arc<AclStorage>) -> Arc<AclStorage> {
+		::std::mem::replace(&mut *self.backend.write(), backend)
+	}
+}
+
 impl OnChainAclStorage {
 	pub fn new(trusted_client: TrustedClient) -> Result<Arc<Self>, Error> {
 		let client = trusted_client.get_untrusted();
@@ -23,7 +23,7 @@ use parking_lot::Mutex;
 use tokio_core::reactor::Core;
 use ethcrypto;
 use ethkey;
-use super::acl_storage::AclStorage;
+use super::acl_storage::AclStorageHandle;
 use super::key_storage::KeyStorage;
 use super::key_server_set::KeyServerSet;
 use key_server_cluster::{math, ClusterCore};
@@ -42,11 +42,12 @@ pub struct KeyServerCore {
 	close: Option<futures::Complete<()>>,
 	handle: Option<thread::JoinHandle<()>>,
 	cluster: Arc<ClusterClient>,
+	acl_storage: Arc<AclStorageHandle>,
 }
 
 impl KeyServerImpl {
 	/// Create new key server instance
-	pub fn new(config: &ClusterConfiguration, key_server_set: Arc<KeyServerSet>, self_key_pair: Arc<NodeKeyPair>, acl_storage: Arc<AclStorage>, key_storage: Arc<KeyStorage>) -> Result<Self, Error> {
+	pub fn new(config: &ClusterConfiguration, key_server_set: Arc<KeyServerSet>, self_key_pair: Arc<NodeKeyPair>, acl_storage: Arc<AclStorageHandle>, key_storage: Arc<KeyStorage>) -> Result<Self, Error> {
 		Ok(KeyServerImpl {
 			data: Arc::new(Mutex::new(KeyServerCore::new(config, key_server_set, self_key_pair, acl_storage, key_storage)?)),
 		})
@@ -58,7 +59,11 @@ impl KeyServerImpl {
 	}
 }
 
-impl KeyServer for KeyServerImpl {}
+impl KeyServer for KeyServerImpl {
+	fn acl_storage(&self) -> Arc<AclStorageHandle> {
+		self.data.lock().acl_storage.clone()
+	}
+}
 
 impl AdminSessionsServer for KeyServerImpl {
 	fn change_servers_set(&self, old_set_signature: RequestSignature, new_set_signature: RequestSignature, new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
@@ -138,7 +143,7 @@ impl MessageSigner for KeyServerImpl {
 			.map_err(|_| Error::BadSignature)?;
 
 		// sign message
-		let signing_session = self.data.lock().cluster.new_signing_session(key_id.clone(), signature.clone(), None, message)?;
+		let signing_session = self.data.lock().cluster.new_signing_session(key_id.clone(), signature.clone(), None, message, Default::default())?;
 		let message_signature = signing_session.wait()?;
 
 		// compose two message signature components into single one
@@ -154,17 +159,18 @@ impl MessageSigner for KeyServerImpl {
 }
 
 impl KeyServerCore {
-	pub fn new(config: &ClusterConfiguration, key_server_set: Arc<KeyServerSet>, self_key_pair: Arc<NodeKeyPair>, acl_storage: Arc<AclStorage>, key_storage: Arc<KeyStorage>) -> Result<Self, Error> {
+	pub fn new(config: &ClusterConfiguration, key_server_set: Arc<KeyServerSet>, self_key_pair: Arc<NodeKeyPair>, acl_storage: Arc<AclStorageHandle>, key_storage: Arc<KeyStorage>) -> Result<Self, Error> {
 		let config = NetClusterConfiguration {
 			threads: config.threads,
 			self_key_pair: self_key_pair,
 			listen_address: (config.listener_address.address.clone(), config.listener_address.port),
 			key_server_set: key_server_set,
 			allow_connecting_to_higher_nodes: config.allow_connecting_to_higher_nodes,
-			acl_storage: acl_storage,
+			acl_storage: acl_storage.clone(),
 			key_storage: key_storage,
 			admin_public: config.admin_public.clone(),
 			auto_migrate_enabled: config.auto_migrate_enabled,
+			signing_session_options: Default::default(),
 		};
 
 		let (stop, stopped) = futures::oneshot();
@@ -189,6 +195,7 @@ impl KeyServerCore {
 			close: Some(stop),
 			handle: Some(handle),
 			cluster: cluster,
+			acl_storage: acl_storage,
 		})
 	}
 }
@@ -210,7 +217,7 @@ pub mod tests {
 	use std::collections::BTreeMap;
 	use ethcrypto;
 	use ethkey::{self, Secret, Random, Generator};
-	use acl_storage::DummyAclStorage;
+	use acl_storage::{DummyAclStorage, AclStorageHandle};
 	use key_storage::tests::DummyKeyStorage;
 	use node_key_pair::PlainNodeKeyPair;
 	use key_server_set::tests::MapKeyServerSet;
@@ -226,7 +233,11 @@ pub mod tests {
 		pub generation_requests_count: AtomicUsize,
 	}
 
-	impl KeyServer for DummyKeyServer {}
+	impl KeyServer for DummyKeyServer {
+		fn acl_storage(&self) -> Arc<AclStorageHandle> {
+			Arc::new(AclStorageHandle::new(Arc::new(DummyAclStorage::default())))
+		}
+	}
 
 	impl AdminSessionsServer for DummyKeyServer {
 		fn change_servers_set(&self, _old_set_signature: RequestSignature, _new_set_signature: RequestSignature, _new_servers_set: BTreeSet<NodeId>) -> Result<(), Error> {
@@ -288,7 +299,7 @@ pub mod tests {
 		let key_servers: Vec<_> = configs.into_iter().enumerate().map(|(i, cfg)|
 			KeyServerImpl::new(&cfg, Arc::new(MapKeyServerSet::new(key_servers_set.clone())),
 				Arc::new(PlainNodeKeyPair::new(key_pairs[i].clone())),
-				Arc::new(DummyAclStorage::default()),
+				Arc::new(AclStorageHandle::new(Arc::new(DummyAclStorage::default()))),
 				Arc::new(DummyKeyStorage::default())).unwrap()
 		).collect();
 
@@ -421,6 +432,29 @@ pub mod tests {
 		}
 	}
 
+	#[test]
+	fn swapped_acl_storage_is_used_by_new_sessions() {
+		//::logger::init_log();
+		let key_servers = make_key_servers(6118, 3);
+
+		// generate server key
+		let server_key_id = Random.generate().unwrap().secret().clone();
+		let requestor_secret = Random.generate().unwrap().secret().clone();
+		let requestor_public = ethkey::KeyPair::from_secret(requestor_secret.clone()).unwrap().public().clone();
+		let signature = ethkey::sign(&requestor_secret, &server_key_id).unwrap();
+		key_servers[0].generate_key(&server_key_id, &signature, 1).unwrap();
+
+		// swap ACL storage on all key servers to the storage, which prohibits access to the key
+		for key_server in &key_servers {
+			let acl_storage = DummyAclStorage::default();
+			acl_storage.prohibit(requestor_public.clone(), (*server_key_id).clone());
+			key_server.acl_storage().swap(Arc::new(acl_storage));
+		}
+
+		// => signing request is rejected
+		assert!(key_servers[0].sign_message(&server_key_id, &signature, H256::from(42)).is_err());
+	}
+
 	#[test]
 	fn decryption_session_is_delegated_when_node_does_not_have_key_share() {
 		//::logger::init_log();
@@ -41,7 +41,7 @@ impl ShareChangeSessionMeta {
 			id: self.id,
 			master_node_id: self.master_node_id,
 			self_node_id: self.self_node_id,
-			threshold: all_nodes_set_len.checked_sub(1).ok_or(Error::ConsensusUnreachable)?,
+			threshold: all_nodes_set_len.checked_sub(1).ok_or(Error::ConsensusUnreachable { retry_after: None })?,
 		})
 	}
 }
@@ -967,6 +967,7 @@ impl JobTransport for ServersSetChangeConsensusTransport {
 			session_nonce: self.nonce,
 			message: ConsensusMessageWithServersSet::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 				is_confirmed: response,
+				signature: None,
 			}),
 		})))
 	}
@@ -201,10 +201,10 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 								if external_id_number == id_number {
 									continue;
 								}
-								return Err(Error::ConsensusUnreachable);
+								return Err(Error::ConsensusUnreachable { retry_after: None });
 							},
 							Some(&None) => (),
-							None => return Err(Error::ConsensusUnreachable),
+							None => return Err(Error::ConsensusUnreachable { retry_after: None }),
 						}
 					}
 
@@ -213,7 +213,7 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 
 				// check that all id_numbers are filled
 				if new_nodes_map.values().any(Option::is_none) {
-					return Err(Error::ConsensusUnreachable);
+					return Err(Error::ConsensusUnreachable { retry_after: None });
 				}
 			}
 		}
@@ -254,7 +254,7 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 		let old_set_signature = old_set_signature.ok_or(Error::InvalidMessage)?;
 		let new_set_signature = new_set_signature.ok_or(Error::InvalidMessage)?;
 		let new_nodes_set = new_nodes_set.ok_or(Error::InvalidMessage)?;
-		let admin_public = self.core.admin_public.as_ref().cloned().ok_or(Error::ConsensusUnreachable)?;
+		let admin_public = self.core.admin_public.as_ref().cloned().ok_or(Error::ConsensusUnreachable { retry_after: None })?;
 
 		// key share version is required on ShareAdd master node
 		let key_share = self.core.key_share.as_ref().ok_or_else(|| Error::KeyStorage("key share is not found on master node".into()))?;
@@ -342,7 +342,7 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 		match &message.message {
 			&ConsensusMessageOfShareAdd::InitializeConsensusSession(ref message)
 				if data.consensus_session.is_none() && sender == &self.core.meta.master_node_id => {
-					let admin_public = self.core.admin_public.as_ref().cloned().ok_or(Error::ConsensusUnreachable)?;
+					let admin_public = self.core.admin_public.as_ref().cloned().ok_or(Error::ConsensusUnreachable { retry_after: None })?;
 					data.consensus_session = Some(ConsensusSession::new(ConsensusSessionParams {
 						meta: self.core.meta.clone().into_consensus_meta(message.new_nodes_map.len())?,
 						consensus_executor: ServersSetChangeAccessJob::new_on_slave(admin_public),
@@ -369,7 +369,7 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 
 					// check that all id_numbers are filled
 					if new_nodes_map.values().any(Option::is_none) {
-						return Err(Error::ConsensusUnreachable);
+						return Err(Error::ConsensusUnreachable { retry_after: None });
 					}
 
 					// check old set of nodes
@@ -464,7 +464,7 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 							continue;
 						}
 
-						return Err(Error::ConsensusUnreachable);
+						return Err(Error::ConsensusUnreachable { retry_after: None });
 					},
 					Some(&None) => (),
 					None => continue, // can happen for isolated nodes
@@ -538,18 +538,18 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 
 				// there must be exactly thresold + 1 nodes in consensus group
 				if consensus_group.len() != key_share.threshold + 1 {
-					return Err(Error::ConsensusUnreachable);
+					return Err(Error::ConsensusUnreachable { retry_after: None });
 				}
 
 				// every non-isolated node must be a part of new_nodes_set
 				let non_isolated_nodes = core.transport.nodes();
 				if key_version.id_numbers.keys().any(|n| non_isolated_nodes.contains(n) && !new_nodes_map.contains_key(n)) {
-					return Err(Error::ConsensusUnreachable);
+					return Err(Error::ConsensusUnreachable { retry_after: None });
 				}
 
 				// there must be at least one new node in new_nodes_map
 				if key_version.id_numbers.len() >= new_nodes_map.len() {
-					return Err(Error::ConsensusUnreachable);
+					return Err(Error::ConsensusUnreachable { retry_after: None });
 				}
 			},
 			false => {
@@ -557,19 +557,19 @@ impl<T> SessionImpl<T> where T: SessionTransport {
 				// but we must be on new nodes set, since this is a ShareAdd session
 				if consensus_group.contains(&core.meta.self_node_id) ||
 					!new_nodes_map.contains_key(&core.meta.self_node_id) {
-					return Err(Error::ConsensusUnreachable);
+					return Err(Error::ConsensusUnreachable { retry_after: None });
 				}
 			},
 		}
 
 		// master node must always be a part of consensus group
 		if !consensus_group.contains(&core.meta.master_node_id) {
-			return Err(Error::ConsensusUnreachable);
+			return Err(Error::ConsensusUnreachable { retry_after: None });
 		}
 
 		// master node must always be a part of new_nodes_map
 		if !new_nodes_map.contains_key(&core.meta.master_node_id) {
-			return Err(Error::ConsensusUnreachable);
+			return Err(Error::ConsensusUnreachable { retry_after: None });
 		}
 
 		Ok(())
@@ -828,6 +828,7 @@ impl JobTransport for IsolatedSessionTransport {
 			session_nonce: self.nonce,
 			message: ConsensusMessageOfShareAdd::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 				is_confirmed: response,
+				signature: None,
 			}),
 		})))
 	}
@@ -1077,7 +1078,7 @@ pub mod tests {
 		assert_eq!(ml.nodes[&master_node_id].session.initialize(Some(ml.version), Some(new_nodes_set),
 			Some(ml.old_set_signature.clone()),
 			Some(ml.new_set_signature.clone())
-		).unwrap_err(), Error::ConsensusUnreachable);
+		).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 	}
 
 	#[test]
@@ -1089,7 +1090,7 @@ pub mod tests {
 		assert_eq!(ml.nodes[&master_node_id].session.initialize(Some(ml.version), Some(new_nodes_set),
 			Some(ml.old_set_signature.clone()),
 			Some(ml.new_set_signature.clone())
-		).unwrap_err(), Error::ConsensusUnreachable);
+		).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 	}
 
 	#[test]
@@ -1287,6 +1288,6 @@ pub mod tests {
 		// initialize session on master node && run to completion (2-of-5)
 		assert_eq!(ml.nodes[&master_node_id].session.initialize(Some(ml.version), Some(new_nodes_set),
 			Some(ml.old_set_signature.clone()),
-			Some(ml.new_set_signature.clone())).map(|_| ()), Err(Error::ConsensusUnreachable));
+			Some(ml.new_set_signature.clone())).map(|_| ()), Err(Error::ConsensusUnreachable { retry_after: None }));
 	}
 }
@@ -609,6 +609,7 @@ impl JobTransport for IsolatedSessionTransport {
 			session_nonce: self.nonce,
 			message: ConsensusMessageWithServersMap::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 				is_confirmed: response,
+				signature: None,
 			}),
 		})))
 	}
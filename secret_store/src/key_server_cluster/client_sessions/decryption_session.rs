@@ -591,6 +591,7 @@ impl JobTransport for DecryptionConsensusTransport {
 			message: ConsensusMessage::InitializeConsensusSession(InitializeConsensusSession {
 				requestor_signature: request.into(),
 				version: version.clone().into(),
+				deadline: None,
 			})
 		})))
 	}
@@ -602,6 +603,7 @@ impl JobTransport for DecryptionConsensusTransport {
 			session_nonce: self.nonce,
 			message: ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 				is_confirmed: response,
+				signature: None,
 			})
 		})))
 	}
@@ -817,7 +819,7 @@ mod tests {
 			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
 			nonce: 0,
 		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())).unwrap();
-		assert_eq!(session.initialize(Default::default(), false), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.initialize(Default::default(), false), Err(Error::ConsensusUnreachable { retry_after: None }));
 	}
 
 	#[test]
@@ -838,6 +840,7 @@ mod tests {
 				message: message::ConsensusMessage::InitializeConsensusSession(message::InitializeConsensusSession {
 					requestor_signature: ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap().into(),
 					version: Default::default(),
+					deadline: None,
 				}),
 			}).unwrap_err(), Error::InvalidMessage);
 	}
@@ -852,6 +855,7 @@ mod tests {
 				message: message::ConsensusMessage::InitializeConsensusSession(message::InitializeConsensusSession {
 					requestor_signature: ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap().into(),
 					version: Default::default(),
+					deadline: None,
 				}),
 		}).unwrap(), ());
 		assert_eq!(sessions[1].on_partial_decryption_requested(sessions[2].node(), &message::RequestPartialDecryption {
@@ -874,6 +878,7 @@ mod tests {
 				message: message::ConsensusMessage::InitializeConsensusSession(message::InitializeConsensusSession {
 					requestor_signature: ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap().into(),
 					version: Default::default(),
+					deadline: None,
 				}),
 		}).unwrap(), ());
 		assert_eq!(sessions[1].on_partial_decryption_requested(sessions[0].node(), &message::RequestPartialDecryption {
@@ -924,7 +929,7 @@ mod tests {
 		let (_, _, _, sessions) = prepare_decryption_sessions();
 		assert!(sessions[0].decrypted_secret().is_none());
 		sessions[0].on_session_timeout();
-		assert_eq!(sessions[0].decrypted_secret().unwrap().unwrap_err(), Error::ConsensusUnreachable);
+		assert_eq!(sessions[0].decrypted_secret().unwrap().unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 	}
 
 	#[test]
@@ -1083,7 +1088,7 @@ mod tests {
 		acl_storages[1].prohibit(key_pair.public().clone(), SessionId::default());
 		acl_storages[2].prohibit(key_pair.public().clone(), SessionId::default());
 
-		assert_eq!(do_messages_exchange(&clusters, &sessions).unwrap_err(), Error::ConsensusUnreachable);
+		assert_eq!(do_messages_exchange(&clusters, &sessions).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 
 		// check that 3 nodes have failed state
 		assert_eq!(sessions[0].state(), ConsensusSessionState::Failed);
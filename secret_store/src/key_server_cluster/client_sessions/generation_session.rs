@@ -66,6 +66,18 @@ pub struct SessionParams {
 	pub nonce: Option<u64>,
 }
 
+/// Ephemeral SessionImpl creation parameters. There's no way to pass key storage to ephemeral session.
+pub struct EphemeralSessionParams {
+	/// SessionImpl identifier.
+	pub id: SessionId,
+	/// Id of node, on which this session is running.
+	pub self_node_id: Public,
+	/// Cluster
+	pub cluster: Arc<Cluster>,
+	/// Session nonce.
+	pub nonce: Option<u64>,
+}
+
 /// Mutable data of distributed key generation session.
 #[derive(Debug)]
 struct SessionData {
@@ -200,6 +212,18 @@ impl SessionImpl {
 		}
 	}
 
+	/// Create new ephemeral generation session (i.e. to generate one-time session key for signing).
+	/// Generated key share is never saved to the key storage.
+	pub fn new_ephemeral(params: EphemeralSessionParams) -> Self {
+		Self::new(SessionParams {
+			id: params.id,
+			self_node_id: params.self_node_id,
+			key_storage: None,
+			cluster: params.cluster,
+			nonce: params.nonce,
+		})
+	}
+
 	/// Get this node Id.
 	pub fn node(&self) -> &NodeId {
 		&self.self_node_id
@@ -14,23 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, BTreeMap};
+use std::io::Write;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use parking_lot::{Mutex, Condvar};
-use ethkey::{Public, Secret, Signature};
-use ethereum_types::H256;
-use key_server_cluster::{Error, NodeId, SessionId, SessionMeta, AclStorage, DocumentKeyShare};
+use tiny_keccak::Keccak;
+use serde_json;
+use ethkey::{self, Public, Secret, Signature};
+use ethereum_types::{H256, Address};
+use key_server_cluster::{Error, NodeId, SessionId, SessionMeta, AclStorage, DocumentKeyShare, DocumentKeyShareVersion, NodeKeyPair,
+	SerializableH256, SerializablePublic, SerializableMessageHash};
+use key_server_cluster::math::{self, MathBackend, DefaultMathBackend};
 use key_server_cluster::cluster::{Cluster};
 use key_server_cluster::cluster_sessions::{SessionIdWithSubSession, ClusterSession};
-use key_server_cluster::generation_session::{SessionImpl as GenerationSession, SessionParams as GenerationSessionParams,
+use key_server_cluster::generation_session::{SessionImpl as GenerationSession, EphemeralSessionParams as GenerationSessionParams,
 	SessionState as GenerationSessionState};
 use key_server_cluster::message::{Message, SigningMessage, SigningConsensusMessage, SigningGenerationMessage,
 	RequestPartialSignature, PartialSignature, SigningSessionCompleted, GenerationMessage, ConsensusMessage, SigningSessionError,
-	InitializeConsensusSession, ConfirmConsensusInitialization, SigningSessionDelegation, SigningSessionDelegationCompleted};
-use key_server_cluster::jobs::job_session::JobTransport;
-use key_server_cluster::jobs::key_access_job::KeyAccessJob;
+	InitializeConsensusSession, ConfirmConsensusInitialization, SigningSessionDelegation, SigningSessionDelegationCompleted,
+	SigningSessionDelegationCancel};
+use key_server_cluster::jobs::job_session::{JobSessionState, JobTransport};
+use key_server_cluster::jobs::key_access_job::{KeyAccessJob, AccessDecisionsCache};
 use key_server_cluster::jobs::signing_job::{PartialSigningRequest, PartialSigningResponse, SigningJob};
-use key_server_cluster::jobs::consensus_session::{ConsensusSessionParams, ConsensusSessionState, ConsensusSession};
+use key_server_cluster::jobs::consensus_session::{ConsensusSessionParams, ConsensusSessionState, ConsensusSession, NodeHealthTracker};
 
 /// Distributed signing session.
 /// Based on "Efficient Multi-Party Digital Signature using Adaptive Secret Sharing for Low-Power Devices in Wireless Network" paper.
@@ -58,8 +66,18 @@ struct SessionCore {
 	pub cluster: Arc<Cluster>,
 	/// Session-level nonce.
 	pub nonce: u64,
+	/// Session creation time.
+	pub created: Instant,
+	/// Session must be completed within this period after creation.
+	pub max_lifetime: Option<Duration>,
+	/// Nodes, which must not reject consensus.
+	pub mandatory_nodes: BTreeSet<NodeId>,
+	/// Logical workload, this session belongs to.
+	pub workload: Option<String>,
 	/// SessionImpl completion condvar.
 	pub completed: Condvar,
+	/// Time, spent by math backend in signing computations.
+	pub compute_time: Arc<Mutex<Duration>>,
 }
 
 /// Signing consensus session type.
@@ -69,23 +87,408 @@ type SigningConsensusSession = ConsensusSession<KeyAccessJob, SigningConsensusTr
 struct SessionData {
 	/// Session state.
 	pub state: SessionState,
+	/// All states this session has passed through (including current one).
+	pub transitions: Vec<SessionState>,
 	/// Message hash.
 	pub message_hash: Option<H256>,
 	/// Key version to use for decryption.
 	pub version: Option<H256>,
+	/// Snapshot of key version, pinned when session is initialized. Used for the whole session lifetime.
+	pub key_version: Option<DocumentKeyShareVersion>,
 	/// Consensus-based signing session.
 	pub consensus_session: SigningConsensusSession,
 	/// Session key generation session.
 	pub generation_session: Option<GenerationSession>,
 	/// Delegation status.
 	pub delegation_status: Option<DelegationStatus>,
+	/// Time, spent by this session in computations and waiting for network.
+	pub timing: TimingBreakdown,
+	/// When consensus establishment has been started.
+	pub consensus_started: Instant,
+	/// Absolute session deadline (milliseconds since UNIX epoch), agreed by all consensus nodes.
+	pub deadline: Option<u64>,
+	/// When session key generation has been started.
+	pub key_generation_started: Instant,
+	/// Session key generation duration, after which warning is reported (session is not failed).
+	pub key_generation_soft_threshold: Option<Duration>,
+	/// Limiter of concurrent session key generation computations, shared by sessions.
+	pub key_generation_limiter: Option<Arc<ComputationLimiter>>,
+	/// Per-key limiter of concurrent session key generation computations, shared by sessions.
+	pub key_generation_per_key_limiter: Option<Arc<PerKeyComputationLimiter>>,
+	/// Maintenance mode of this node, shared by sessions.
+	pub maintenance_mode: Option<Arc<MaintenanceMode>>,
+	/// True if consensus confirmations must be signed by confirming nodes (on master node).
+	pub require_signed_confirmations: bool,
+	/// Maximal time to wait for all invited nodes to respond after consensus is established (on master node).
+	pub full_consensus_wait: Option<Duration>,
+	/// When consensus has been established, while session key generation has been deferred (on master node).
+	pub consensus_established: Option<Instant>,
+	/// Key pair, used to sign attestation of signing nodes.
+	pub attestation_key_pair: Option<Arc<NodeKeyPair>>,
+	/// Attestation of signing nodes.
+	pub attestation: Option<SigningAttestation>,
+	/// Nodes, which are generating session key (nonce) on master node.
+	pub nonce_generation_nodes: BTreeSet<NodeId>,
+	/// Nodes, which partial signatures have been received by master, in order of arrival.
+	pub partial_arrival_order: Vec<(NodeId, Instant)>,
+	/// Cache of recently computed signatures.
+	pub signature_cache: Option<Arc<SignatureCache>>,
+	/// Delay before retrying the request, suggested per every missing node when consensus is unreachable.
+	/// When not set, CONSENSUS_RETRY_BACKOFF is used.
+	pub consensus_retry_backoff: Option<Duration>,
+	/// Blind secret share when computing partial signature.
+	pub partial_signature_blinding: bool,
+	/// Signer organizations policy.
+	pub organizations_policy: Option<OrganizationsPolicy>,
+	/// Mapper, applied to the session error before it is stored.
+	pub error_mapper: Option<Arc<ErrorMapper>>,
+	/// Signing metrics receiver.
+	pub metrics: Option<Arc<SigningMetrics>>,
+	/// Writer of session outcome events.
+	pub event_writer: Option<Arc<Mutex<Box<Write + Send>>>>,
+	/// Transport-level identity of requester and verifier, binding it to the requester address.
+	pub requester_identity: Option<(String, Arc<RequesterIdentityVerifier>)>,
+	/// Listener of denied signing requests.
+	pub denial_listener: Option<Arc<DenialListener>>,
+	/// Backend of signing computations.
+	pub math_backend: Arc<MathBackend>,
+	/// True if master must only sign hashes of canonically encoded structured messages.
+	pub require_canonical_message: bool,
+	/// Node, error of which has caused session failure.
+	pub failed_node: Option<NodeId>,
 	/// Decryption result.
 	pub result: Option<Result<(Secret, Secret), Error>>,
 }
 
+/// Conversion of computed signature into other representation.
+pub trait FromSignature: Sized {
+	/// Convert signature (c, s) into target representation.
+	fn from_signature(c: &Secret, s: &Secret) -> Result<Self, Error>;
+}
+
+/// Signature, encoded as DER SEQUENCE { c INTEGER, s INTEGER }.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerSignature(pub Vec<u8>);
+
+/// Signature, encoded as 64 bytes: c || s.
+#[derive(Clone)]
+pub struct CompactSignature(pub [u8; 64]);
+
+/// Signature scheme.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SignatureScheme {
+	/// Schnorr signature over secp256k1 curve.
+	SchnorrSecp256k1,
+}
+
+/// Signature, tagged with scheme, which has produced it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaggedSignature {
+	/// Signature scheme.
+	pub scheme: SignatureScheme,
+	/// Signature (c, s).
+	pub signature: (Secret, Secret),
+}
+
+/// Cross-checks transport-level identity of requester (i.e. derived from TLS client certificate)
+/// with the requester address, recovered from the request signature.
+pub trait RequesterIdentityVerifier: Send + Sync {
+	/// Returns true if transport identity belongs to the requester with given address.
+	fn verify(&self, transport_identity: &str, requester: &Address) -> bool;
+}
+
+/// Reason of signing request denial.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DenialReason {
+	/// Access has been rejected by given nodes (because of ACL or because key share is missing).
+	Rejected(BTreeSet<NodeId>),
+	/// Access has been rejected by given mandatory node.
+	RejectedByMandatoryNode(NodeId),
+	/// Requester doesn't match its transport-level identity.
+	TransportIdentityMismatch,
+}
+
+/// Denied signing request. Never contains secret material.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningDenial {
+	/// Id of the key, access to which has been denied.
+	pub key_id: SessionId,
+	/// Address of requester, if known.
+	pub requester: Option<Address>,
+	/// Denial reason.
+	pub reason: DenialReason,
+}
+
+/// Receiver of denied signing requests (i.e. for security monitoring).
+pub trait DenialListener: Send + Sync {
+	/// When signing request is denied.
+	fn on_denied(&self, denial: &SigningDenial);
+}
+
+/// Denial listener, which writes every denial to the log.
+#[derive(Default)]
+pub struct LogDenialListener;
+
+/// Receiver of signing sessions metrics.
+pub trait SigningMetrics: Send + Sync {
+	/// When signing session is started on master node.
+	fn on_session_started(&self, _workload: Option<&str>) {}
+	/// When signing session is completed, either successfully or with an error.
+	fn on_session_completed(&self, workload: Option<&str>, is_succeeded: bool, duration: Duration);
+	/// When session key generation has taken longer than configured soft threshold.
+	fn on_slow_session_key_generation(&self, _workload: Option<&str>, _duration: Duration) {}
+}
+
+/// Upper bounds (in seconds) of signing duration histogram buckets.
+/// Delay (in seconds) before retrying the request, suggested per every node, missing to reach consensus.
+const CONSENSUS_RETRY_BACKOFF: u64 = 5;
+
+const PROMETHEUS_DURATION_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Signing metrics, which could be rendered in Prometheus text exposition format.
+#[derive(Default)]
+pub struct PrometheusSessionMetrics {
+	/// Metrics data.
+	data: Mutex<PrometheusSessionMetricsData>,
+}
+
+/// Mutable data of Prometheus signing metrics.
+#[derive(Default)]
+struct PrometheusSessionMetricsData {
+	/// Number of started sessions.
+	started: u64,
+	/// Number of successfully completed sessions.
+	completed: u64,
+	/// Number of failed sessions.
+	failed: u64,
+	/// Number of sessions with slow session key generation.
+	slow_key_generations: u64,
+	/// Number of sessions, which have completed within every of PROMETHEUS_DURATION_BUCKETS.
+	duration_buckets: [u64; 11],
+	/// Sum of all sessions durations (in seconds).
+	duration_sum: f64,
+}
+
+impl PrometheusSessionMetrics {
+	/// Render metrics in Prometheus text exposition format.
+	pub fn render(&self) -> String {
+		let data = self.data.lock();
+		let mut rendered = String::new();
+		for &(name, help, value) in &[
+			("secretstore_signing_sessions_started_total", "Number of signing sessions, started on this node.", data.started),
+			("secretstore_signing_sessions_completed_total", "Number of successfully completed signing sessions.", data.completed),
+			("secretstore_signing_sessions_failed_total", "Number of failed signing sessions.", data.failed),
+			("secretstore_signing_slow_key_generations_total", "Number of signing sessions with slow session key generation.", data.slow_key_generations),
+		] {
+			rendered.push_str(&format!("# HELP {} {}\n# TYPE {} counter\n{} {}\n", name, help, name, name, value));
+		}
+
+		let name = "secretstore_signing_duration_seconds";
+		rendered.push_str(&format!("# HELP {} Duration of signing sessions.\n# TYPE {} histogram\n", name, name));
+		for (bound, count) in PROMETHEUS_DURATION_BUCKETS.iter().zip(data.duration_buckets.iter()) {
+			rendered.push_str(&format!("{}_bucket{{le=\"{}\"}} {}\n", name, bound, count));
+		}
+		let count = data.completed + data.failed;
+		rendered.push_str(&format!("{}_bucket{{le=\"+Inf\"}} {}\n", name, count));
+		rendered.push_str(&format!("{}_sum {}\n", name, data.duration_sum));
+		rendered.push_str(&format!("{}_count {}\n", name, count));
+		rendered
+	}
+}
+
+impl SigningMetrics for PrometheusSessionMetrics {
+	fn on_session_started(&self, _workload: Option<&str>) {
+		self.data.lock().started += 1;
+	}
+
+	fn on_session_completed(&self, _workload: Option<&str>, is_succeeded: bool, duration: Duration) {
+		let mut data = self.data.lock();
+		if is_succeeded {
+			data.completed += 1;
+		} else {
+			data.failed += 1;
+		}
+
+		let duration = duration.as_secs() as f64 + duration.subsec_nanos() as f64 / 1_000_000_000f64;
+		for (bound, count) in PROMETHEUS_DURATION_BUCKETS.iter().zip(data.duration_buckets.iter_mut()) {
+			if duration <= *bound {
+				*count += 1;
+			}
+		}
+		data.duration_sum += duration;
+	}
+
+	fn on_slow_session_key_generation(&self, _workload: Option<&str>, _duration: Duration) {
+		self.data.lock().slow_key_generations += 1;
+	}
+}
+
+/// Bundle of signing session failure details. Contains no secrets.
+#[derive(Debug, Clone, Serialize)]
+pub struct FailureReport {
+	/// Session error.
+	pub error: String,
+	/// Session state, in which session has failed.
+	pub phase: SessionState,
+	/// Other node, error of which has caused session failure.
+	pub failed_node: Option<SerializablePublic>,
+	/// Session state transitions.
+	pub transitions: Vec<SessionState>,
+	/// Session diagnostics at the moment of report creation.
+	pub diagnostics: SessionDiagnostics,
+	/// Latencies of partial signatures (since consensus establishment has started), in milliseconds.
+	pub partial_signature_latencies_ms: Vec<(SerializablePublic, u64)>,
+}
+
+/// Outcome of signing session, written as a single line of JSON when session is completed. Never contains secret material.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEvent {
+	/// Session id.
+	pub session_id: SerializableH256,
+	/// Session outcome: either "succeeded", or "failed".
+	pub outcome: String,
+	/// Session duration, in milliseconds.
+	pub duration_ms: u64,
+	/// Nodes, which partial signatures have been received by this node.
+	pub signers: Vec<SerializablePublic>,
+	/// Session error, if failed.
+	pub error: Option<String>,
+}
+
+/// Signing session error with the context of failure.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningError {
+	/// Session error.
+	pub error: Error,
+	/// Session state, in which session has failed.
+	pub phase: SessionState,
+	/// Other node, error of which has caused session failure.
+	pub node: Option<NodeId>,
+	/// Human-readable hint on how to resolve the error.
+	pub remediation: String,
+}
+
+/// Attestation of nodes, which have computed partial signatures. Signed by master node key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SigningAttestation {
+	/// Nodes, which have computed partial signatures.
+	pub signers: BTreeSet<NodeId>,
+	/// Signature of keccak(session_id || message_hash || version || signers), made with master node key.
+	pub signature: Signature,
+}
+
+/// Per-node status of consensus establishing.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ConsensusDetail {
+	/// Nodes, which have confirmed consensus.
+	pub confirmed: BTreeSet<NodeId>,
+	/// Nodes, which have not responded yet.
+	pub pending: BTreeSet<NodeId>,
+	/// Nodes, which have rejected consensus.
+	pub rejected: BTreeSet<NodeId>,
+}
+
+/// Time, spent by signing session.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TimingBreakdown {
+	/// Time, spent by callers, explicitly waiting for session completion.
+	pub network: Duration,
+	/// Time, spent by math backend in signing computations.
+	pub compute: Duration,
+	/// Time, spent establishing consensus (including ACL checks on all consensus nodes).
+	/// This overlaps with both network and compute time.
+	pub consensus: Duration,
+}
+
+/// Sibling node of Merkle inclusion proof.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MerkleProofNode {
+	/// True if sibling is the left child of the parent node.
+	pub is_left: bool,
+	/// Sibling node hash.
+	pub hash: H256,
+}
+
+/// Limits number of concurrent computations, shared by multiple sessions.
+pub struct ComputationLimiter {
+	/// Maximal number of concurrent computations.
+	max_concurrent: usize,
+	/// Number of active computations.
+	active: Mutex<usize>,
+	/// Signalled when computation is completed.
+	completed: Condvar,
+}
+
+/// Permit to run computation. Computation slot is released when permit is dropped.
+pub struct ComputationPermit<'a> {
+	/// Limiter, which has issued this permit.
+	limiter: &'a ComputationLimiter,
+}
+
+/// Limits number of concurrent computations for every key, shared by multiple sessions.
+pub struct PerKeyComputationLimiter {
+	/// Maximal number of concurrent computations for single key.
+	max_concurrent_per_key: usize,
+	/// Number of active computations by key id.
+	active: Mutex<BTreeMap<SessionId, usize>>,
+	/// Signalled when computation is completed.
+	completed: Condvar,
+}
+
+/// Permit to run computation for given key. Computation slot is released when permit is dropped.
+pub struct PerKeyComputationPermit<'a> {
+	/// Limiter, which has issued this permit.
+	limiter: &'a PerKeyComputationLimiter,
+	/// Key id, this permit is issued for.
+	key_id: SessionId,
+}
+
+/// Maintenance mode of the node. While enabled, new signing sessions are rejected, but already
+/// initialized sessions are allowed to complete.
+#[derive(Default)]
+pub struct MaintenanceMode {
+	/// True if node is in maintenance mode.
+	enabled: AtomicBool,
+}
+
+/// Session progress at the moment, when `wait_until` deadline has passed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaitTimeout {
+	/// Session state.
+	pub state: SessionState,
+	/// Number of partial signatures, collected by master node. Always zero on slave nodes.
+	pub partial_signatures_count: usize,
+}
+
+/// Cache of recently computed signatures, used to serve retried requests without running the protocol again.
+/// Signatures are cached per requester, so that signature is never served to other requester.
+pub struct SignatureCache {
+	/// Time to live of every cache entry.
+	ttl: Duration,
+	/// Cached signatures by (key id, requester, key version, message hash).
+	entries: Mutex<BTreeMap<(SessionId, Public, H256, H256), (Instant, (Secret, Secret))>>,
+}
+
+/// Organization identifier.
+pub type OrgId = H256;
+
+/// Policy, which requires consensus group to span at least given number of distinct organizations.
+#[derive(Debug, Clone)]
+pub struct OrganizationsPolicy {
+	/// Organization of every node.
+	pub node_org: BTreeMap<NodeId, OrgId>,
+	/// Minimal number of distinct organizations, which must provide partial signatures.
+	pub min_orgs: usize,
+}
+
+/// Maps session errors onto integration-specific errors.
+pub trait ErrorMapper: Send + Sync {
+	/// Map session error.
+	fn map_error(&self, error: Error) -> Error;
+}
+
 /// Signing session state.
-#[derive(Debug, PartialEq)]
-#[cfg_attr(test, derive(Clone, Copy))]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum SessionState {
 	/// State when consensus is establishing.
 	ConsensusEstablishing,
@@ -95,6 +498,43 @@ pub enum SessionState {
 	SignatureComputing,
 }
 
+/// Snapshot of signing session state, suitable for debugging. Never contains secret material.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionDiagnostics {
+	/// Session id.
+	pub id: SerializableH256,
+	/// This node id.
+	pub self_node_id: SerializablePublic,
+	/// Master node id.
+	pub master_node_id: SerializablePublic,
+	/// Is this node a master node of the session.
+	pub is_master: bool,
+	/// Key threshold.
+	pub threshold: usize,
+	/// Session state.
+	pub state: SessionState,
+	/// Key version to use for signing.
+	pub version: Option<SerializableH256>,
+	/// Hash of the message to sign.
+	pub message_hash: Option<SerializableMessageHash>,
+	/// Is session key generation started.
+	pub is_session_key_generation_started: bool,
+	/// Is session key generated.
+	pub is_session_key_generated: bool,
+	/// Number of partial signatures, received by this node.
+	pub partial_signatures_count: usize,
+	/// Node, to which this session has been delegated.
+	pub delegated_to: Option<SerializablePublic>,
+	/// Node, from which this session has been delegated.
+	pub delegated_from: Option<SerializablePublic>,
+	/// Time passed since session creation, in milliseconds.
+	pub age_ms: u64,
+	/// Logical workload, this session belongs to.
+	pub workload: Option<String>,
+	/// Session error, if session has failed.
+	pub error: Option<String>,
+}
+
 /// Session creation parameters
 pub struct SessionParams {
 	/// Session metadata.
@@ -109,6 +549,83 @@ pub struct SessionParams {
 	pub cluster: Arc<Cluster>,
 	/// Session nonce.
 	pub nonce: u64,
+	/// Session must be completed within this period after creation. Unlimited if None.
+	pub max_lifetime: Option<Duration>,
+	/// Nodes, which must not reject consensus. If any of these nodes rejects, session fails with AccessDenied.
+	pub mandatory_nodes: BTreeSet<NodeId>,
+	/// Options of signing request, this session is started for.
+	pub request_options: SigningRequestOptions,
+	/// Options, shared by all signing sessions of this node.
+	pub options: SigningSessionOptions,
+}
+
+/// Options of single signing request. Passed by the client, starting signing session on master node.
+#[derive(Debug, Clone, Default)]
+pub struct SigningRequestOptions {
+	/// Logical workload, this request belongs to. This is local metadata, used in metrics and diagnostics.
+	pub workload: Option<String>,
+	/// Absolute session deadline, which is communicated to all consensus nodes.
+	pub deadline: Option<SystemTime>,
+	/// Transport-level identity of requester, which must be verified against requester address before signing.
+	/// Only verified if node has configured requester identity verifier.
+	pub transport_identity: Option<String>,
+}
+
+/// Options of signing sessions. Owned by session creator and passed to every signing session it creates.
+#[derive(Clone, Default)]
+pub struct SigningSessionOptions {
+	/// Mapper, applied to the session error before it is returned from `wait`.
+	pub error_mapper: Option<Arc<ErrorMapper>>,
+	/// Policy, which consensus group of sessions, started by this node, must satisfy.
+	pub organizations_policy: Option<OrganizationsPolicy>,
+	/// Cache of recently computed signatures.
+	pub signature_cache: Option<Arc<SignatureCache>>,
+	/// Blind secret share of this node when computing partial signature. Signature is the same with or without blinding.
+	pub partial_signature_blinding: bool,
+	/// Delay before retrying the request, suggested per every missing node when consensus is unreachable.
+	/// When not set, CONSENSUS_RETRY_BACKOFF is used.
+	pub consensus_retry_backoff: Option<Duration>,
+	/// Send consensus and partial signature requests in batches of given size.
+	pub requests_batch_size: Option<usize>,
+	/// Key pair, used to sign attestation of signing nodes when signature is computed.
+	pub attestation_key_pair: Option<Arc<NodeKeyPair>>,
+	/// Only accept structured messages on master node, to sign hash of their canonical encoding.
+	pub require_canonical_message: bool,
+	/// Cache of ACL decisions, used when establishing consensus.
+	pub access_decisions_cache: Option<Arc<AccessDecisionsCache>>,
+	/// Receiver of signing sessions metrics.
+	pub metrics: Option<Arc<SigningMetrics>>,
+	/// Session key generation duration, after which warning is logged and reported to metrics receiver.
+	/// Session is not failed when this threshold is exceeded.
+	pub key_generation_soft_threshold: Option<Duration>,
+	/// Limiter of session key generation computations. Sharing the same limiter between sessions
+	/// prevents session key generation of many concurrent sessions from monopolizing all cores.
+	pub key_generation_limiter: Option<Arc<ComputationLimiter>>,
+	/// Health tracker of nodes. It is updated with results of sessions and used to prefer healthier nodes
+	/// when selecting consensus group.
+	pub node_health_tracker: Option<Arc<NodeHealthTracker>>,
+	/// Writer of session outcome events. Every completed session writes single JSON event, followed by newline.
+	pub event_writer: Option<Arc<Mutex<Box<Write + Send>>>>,
+	/// Verifier, binding transport-level identity of requester to the requester address.
+	pub requester_identity_verifier: Option<Arc<RequesterIdentityVerifier>>,
+	/// Per-key limiter of session key generation computations. Unlike global limiter, this only
+	/// serializes computations of sessions for the same key, so that hot key doesn't overwhelm its holders.
+	pub key_generation_per_key_limiter: Option<Arc<PerKeyComputationLimiter>>,
+	/// Listener of signing requests, denied by this node.
+	pub denial_listener: Option<Arc<DenialListener>>,
+	/// Backend of signing computations (i.e. hardware-accelerated one). DefaultMathBackend is used when not set.
+	pub math_backend: Option<Arc<MathBackend>>,
+	/// Maintenance mode of this node. New sessions are rejected on initialization while node is in maintenance mode.
+	pub maintenance_mode: Option<Arc<MaintenanceMode>>,
+	/// Key pair of this node, used to sign consensus confirmations. Signature is verified by master node before
+	/// confirmation is counted, so that confirmation can't be spoofed on transport level. Filled by session creator.
+	pub confirmation_key_pair: Option<Arc<NodeKeyPair>>,
+	/// Reject consensus confirmations, which are not signed by confirming node. Signed confirmations are always verified.
+	pub require_signed_confirmations: bool,
+	/// Defer session key generation until all invited nodes have responded to consensus request, but no longer than
+	/// this period after consensus is established (or until session timeout). Trades latency for broader choice of
+	/// consensus group.
+	pub full_consensus_wait: Option<Duration>,
 }
 
 /// Signing consensus transport.
@@ -121,6 +638,10 @@ struct SigningConsensusTransport {
 	nonce: u64,
 	/// Selected key version (on master node).
 	version: Option<H256>,
+	/// Session deadline (on master node).
+	deadline: Option<u64>,
+	/// Key pair, used to sign consensus confirmations (on slave nodes).
+	confirmation_key_pair: Option<Arc<NodeKeyPair>>,
 	/// Cluster.
 	cluster: Arc<Cluster>,
 }
@@ -149,6 +670,14 @@ struct SigningJobTransport {
 	cluster: Arc<Cluster>,
 }
 
+/// Math backend, which accounts time, spent in computations.
+struct TimedMathBackend {
+	/// Actual math backend.
+	backend: Arc<MathBackend>,
+	/// Accumulated computation time.
+	compute_time: Arc<Mutex<Duration>>,
+}
+
 /// Session delegation status.
 enum DelegationStatus {
 	/// Delegated to other node.
@@ -160,16 +689,39 @@ enum DelegationStatus {
 impl SessionImpl {
 	/// Create new signing session.
 	pub fn new(params: SessionParams, requester_signature: Option<Signature>) -> Result<Self, Error> {
-		debug_assert_eq!(params.meta.threshold, params.key_share.as_ref().map(|ks| ks.threshold).unwrap_or_default());
+		// session threshold must match key threshold, otherwise consensus group size is wrong
+		if params.meta.threshold != params.key_share.as_ref().map(|ks| ks.threshold).unwrap_or_default() {
+			return Err(Error::InconsistentSessionParams);
+		}
+
+		// zero access key is never generated by master => it is either a bug or a crafted message
+		if params.access_key.is_zero() {
+			return Err(Error::InconsistentSessionParams);
+		}
+
+		if let Some(key_share) = params.key_share.as_ref() {
+			// access key must never be equal to the secret share of this node
+			if key_share.versions.iter().any(|version| version.secret_share == params.access_key) {
+				return Err(Error::InvalidMessage);
+			}
+
+			// this node must be one of key share owners
+			if !key_share.versions.is_empty() && !key_share.versions.iter().any(|version| version.id_numbers.contains_key(&params.meta.self_node_id)) {
+				return Err(Error::InconsistentSessionParams);
+			}
+		}
 
+		let deadline = params.request_options.deadline.map(to_unix_millis);
 		let consensus_transport = SigningConsensusTransport {
 			id: params.meta.id.clone(),
 			access_key: params.access_key.clone(),
 			nonce: params.nonce,
 			version: None,
+			deadline: deadline,
+			confirmation_key_pair: params.options.confirmation_key_pair.clone(),
 			cluster: params.cluster.clone(),
 		};
-		let consensus_session = ConsensusSession::new(ConsensusSessionParams {
+		let mut consensus_session = ConsensusSession::new(ConsensusSessionParams {
 			meta: params.meta.clone(),
 			consensus_executor: match requester_signature {
 				Some(requester_signature) => KeyAccessJob::new_on_master(params.meta.id.clone(), params.acl_storage.clone(), requester_signature),
@@ -177,6 +729,13 @@ impl SessionImpl {
 			},
 			consensus_transport: consensus_transport,
 		})?;
+		consensus_session.set_requests_batch_size(params.options.requests_batch_size);
+		if let Some(access_decisions_cache) = params.options.access_decisions_cache.clone() {
+			consensus_session.consensus_job_mut().executor_mut().set_decisions_cache(access_decisions_cache);
+		}
+		if let Some(node_health_tracker) = params.options.node_health_tracker.clone() {
+			consensus_session.set_node_health_tracker(node_health_tracker);
+		}
 
 		Ok(SessionImpl {
 			core: SessionCore {
@@ -185,29 +744,343 @@ impl SessionImpl {
 				key_share: params.key_share,
 				cluster: params.cluster,
 				nonce: params.nonce,
+				created: Instant::now(),
+				max_lifetime: params.max_lifetime,
+				mandatory_nodes: params.mandatory_nodes,
+				workload: params.request_options.workload,
 				completed: Condvar::new(),
+				compute_time: Default::default(),
 			},
 			data: Mutex::new(SessionData {
 				state: SessionState::ConsensusEstablishing,
+				transitions: vec![SessionState::ConsensusEstablishing],
 				message_hash: None,
 				version: None,
+				key_version: None,
 				consensus_session: consensus_session,
 				generation_session: None,
 				delegation_status: None,
+				timing: Default::default(),
+				consensus_started: Instant::now(),
+				deadline: deadline,
+				key_generation_started: Instant::now(),
+				key_generation_soft_threshold: params.options.key_generation_soft_threshold,
+				key_generation_limiter: params.options.key_generation_limiter,
+				key_generation_per_key_limiter: params.options.key_generation_per_key_limiter,
+				maintenance_mode: params.options.maintenance_mode,
+				require_signed_confirmations: params.options.require_signed_confirmations,
+				full_consensus_wait: params.options.full_consensus_wait,
+				consensus_established: None,
+				attestation_key_pair: params.options.attestation_key_pair,
+				attestation: None,
+				nonce_generation_nodes: BTreeSet::new(),
+				partial_arrival_order: Vec::new(),
+				signature_cache: params.options.signature_cache,
+				consensus_retry_backoff: params.options.consensus_retry_backoff,
+				partial_signature_blinding: params.options.partial_signature_blinding,
+				organizations_policy: params.options.organizations_policy,
+				error_mapper: params.options.error_mapper,
+				metrics: params.options.metrics,
+				event_writer: params.options.event_writer,
+				requester_identity: match (params.request_options.transport_identity, params.options.requester_identity_verifier) {
+					(Some(transport_identity), Some(verifier)) => Some((transport_identity, verifier)),
+					_ => None,
+				},
+				denial_listener: params.options.denial_listener,
+				math_backend: params.options.math_backend.unwrap_or_else(|| Arc::new(DefaultMathBackend)),
+				require_canonical_message: params.options.require_canonical_message,
+				failed_node: None,
 				result: None,
 			}),
 		})
 	}
 
 	/// Get session state.
-	#[cfg(test)]
 	pub fn state(&self) -> SessionState {
 		self.data.lock().state
 	}
 
+	/// Get all states this session has passed through.
+	pub fn state_transitions(&self) -> Vec<SessionState> {
+		self.data.lock().transitions.clone()
+	}
+
+	/// Get approximate session progress in [0.0, 1.0] range. Phases are weighted by typical number of messages
+	/// they're exchanging. Progress is only meaningful on master node.
+	pub fn progress(&self) -> f32 {
+		const CONSENSUS_WEIGHT: f32 = 0.2;
+		const KEY_GENERATION_WEIGHT: f32 = 0.6;
+		const SIGNATURE_COMPUTING_WEIGHT: f32 = 0.2;
+
+		let data = self.data.lock();
+		if data.result.is_some() {
+			return 1.0;
+		}
+
+		let required_nodes = self.core.key_share.as_ref().map(|ks| ks.threshold).unwrap_or(0) + 1;
+		let fraction = |count: usize| ::std::cmp::min(count, required_nodes) as f32 / required_nodes as f32;
+		match data.state {
+			SessionState::ConsensusEstablishing => {
+				let accepted_nodes = data.consensus_session.consensus_job().responses().values().filter(|r| **r).count();
+				CONSENSUS_WEIGHT * fraction(accepted_nodes)
+			},
+			SessionState::SessionKeyGeneration => {
+				let key_generation_progress = match data.generation_session.as_ref().map(|s| s.state()) {
+					None | Some(GenerationSessionState::WaitingForInitialization) | Some(GenerationSessionState::Failed) => 0.0,
+					Some(GenerationSessionState::WaitingForInitializationConfirm(_)) => 0.2,
+					Some(GenerationSessionState::WaitingForInitializationComplete) => 0.3,
+					Some(GenerationSessionState::WaitingForKeysDissemination) => 0.4,
+					Some(GenerationSessionState::WaitingForPublicKeyShare) => 0.6,
+					Some(GenerationSessionState::WaitingForGenerationConfirmation) => 0.8,
+					Some(GenerationSessionState::Finished) => 1.0,
+				};
+				CONSENSUS_WEIGHT + KEY_GENERATION_WEIGHT * key_generation_progress
+			},
+			SessionState::SignatureComputing => CONSENSUS_WEIGHT + KEY_GENERATION_WEIGHT
+				+ SIGNATURE_COMPUTING_WEIGHT * fraction(data.consensus_session.computation_responses_count()),
+		}
+	}
+
+	/// Get per-node status of consensus establishing. Empty on slave nodes and before initialization.
+	pub fn consensus_detail(&self) -> ConsensusDetail {
+		if self.core.meta.self_node_id != self.core.meta.master_node_id {
+			return Default::default();
+		}
+
+		let data = self.data.lock();
+		let consensus_job = data.consensus_session.consensus_job();
+		if consensus_job.state() == JobSessionState::Inactive {
+			return Default::default();
+		}
+
+		ConsensusDetail {
+			confirmed: consensus_job.responses().keys().cloned().collect(),
+			pending: consensus_job.requests().clone(),
+			rejected: consensus_job.rejects().clone(),
+		}
+	}
+
+	/// Get number of confirmations, which are required to establish consensus.
+	/// Returns None if this node is not a master node, or if session is not establishing consensus.
+	pub fn consensus_remaining(&self) -> Option<usize> {
+		if self.core.meta.self_node_id != self.core.meta.master_node_id {
+			return None;
+		}
+
+		let data = self.data.lock();
+		if data.state != SessionState::ConsensusEstablishing || data.consensus_session.state() != ConsensusSessionState::EstablishingConsensus {
+			return None;
+		}
+
+		let confirmations_count = data.consensus_session.consensus_job().responses_count();
+		Some((self.core.meta.threshold + 1).saturating_sub(confirmations_count))
+	}
+
+	/// Get attestation of signing nodes.
+	pub fn attestation(&self) -> Option<SigningAttestation> {
+		self.data.lock().attestation.clone()
+	}
+
+	/// Get nodes, which have contributed to generation of session key (nonce). Only available on master node
+	/// after session key is generated. This could differ from signers if node fails after session key is generated.
+	pub fn nonce_contributors(&self) -> Option<BTreeSet<NodeId>> {
+		let data = self.data.lock();
+		match data.state {
+			SessionState::SignatureComputing if !data.nonce_generation_nodes.is_empty() => Some(data.nonce_generation_nodes.clone()),
+			_ => None,
+		}
+	}
+
+	/// Get joint public of session key (nonce), which signature commits to. Only available after session key is generated.
+	pub fn nonce_public(&self) -> Option<Public> {
+		let data = self.data.lock();
+		if data.state != SessionState::SignatureComputing {
+			return None;
+		}
+
+		data.generation_session.as_ref()
+			.and_then(|generation_session| generation_session.joint_public_and_secret())
+			.and_then(|joint_public_and_secret| joint_public_and_secret.ok())
+			.map(|(public, _)| public)
+	}
+
+	/// Get nodes, which partial signatures have been received by master in current signature computation round.
+	/// Only available on master node after signature computation is started.
+	pub fn signers(&self) -> Option<BTreeSet<NodeId>> {
+		self.data.lock().consensus_session.computation_responses()
+			.map(|responses| responses.keys().cloned().collect())
+	}
+
+	/// Get Lagrange coefficients of signers, which have been applied to their secret shares when computing partial signatures.
+	/// Partial signature of signer i is k(i) - c * coeff(i) * share(i) and signature is the sum of partial signatures.
+	/// Only available on master node, once partial signatures are requested.
+	pub fn lagrange_coefficients(&self) -> Option<BTreeMap<NodeId, Secret>> {
+		let data = self.data.lock();
+		let signers: BTreeSet<NodeId> = data.consensus_session.computation_responses()?.keys().cloned().collect();
+		let key_version = data.key_version.as_ref()?;
+		let threshold = self.core.key_share.as_ref()?.threshold;
+		signers.iter().map(|signer| {
+			let node_number = key_version.id_numbers.get(signer)?;
+			let other_nodes_numbers = signers.iter()
+				.filter(|n| *n != signer)
+				.map(|n| key_version.id_numbers.get(n))
+				.collect::<Option<Vec<_>>>()?;
+			math::compute_lagrange_coefficient(threshold, node_number, other_nodes_numbers.into_iter()).ok()
+				.map(|coeff| (signer.clone(), coeff))
+		}).collect()
+	}
+
+	/// Get nodes, which partial signatures have been received by master, in order of arrival.
+	pub fn partial_arrival_order(&self) -> Vec<(NodeId, Instant)> {
+		self.data.lock().partial_arrival_order.clone()
+	}
+
+	/// Get time, spent by this session in computations and waiting for network.
+	pub fn timing_breakdown(&self) -> TimingBreakdown {
+		let mut timing = self.data.lock().timing;
+		timing.compute = *self.core.compute_time.lock();
+		timing
+	}
+
+	/// Get diagnostics snapshot of the session.
+	pub fn diagnostics(&self) -> SessionDiagnostics {
+		let data = self.data.lock();
+		let age = self.core.created.elapsed();
+		SessionDiagnostics {
+			id: self.core.meta.id.clone().into(),
+			self_node_id: self.core.meta.self_node_id.clone().into(),
+			master_node_id: self.core.meta.master_node_id.clone().into(),
+			is_master: self.core.meta.self_node_id == self.core.meta.master_node_id,
+			threshold: self.core.meta.threshold,
+			state: data.state,
+			version: data.version.clone().map(Into::into),
+			message_hash: data.message_hash.clone().map(Into::into),
+			is_session_key_generation_started: data.generation_session.is_some(),
+			is_session_key_generated: data.generation_session.as_ref()
+				.map(|s| s.joint_public_and_secret().is_some())
+				.unwrap_or(false),
+			partial_signatures_count: data.consensus_session.computation_responses_count(),
+			delegated_to: match data.delegation_status {
+				Some(DelegationStatus::DelegatedTo(ref node)) => Some(node.clone().into()),
+				_ => None,
+			},
+			delegated_from: match data.delegation_status {
+				Some(DelegationStatus::DelegatedFrom(ref node, _)) => Some(node.clone().into()),
+				_ => None,
+			},
+			age_ms: age.as_secs() * 1_000 + (age.subsec_nanos() / 1_000_000) as u64,
+			workload: self.core.workload.clone(),
+			error: match data.result {
+				Some(Err(ref error)) => Some(format!("{}", error)),
+				_ => None,
+			},
+		}
+	}
+
+	/// Get report on session failure. Returns None if session has not failed.
+	pub fn failure_report(&self) -> Option<FailureReport> {
+		let diagnostics = self.diagnostics();
+		let data = self.data.lock();
+		let error = match data.result {
+			Some(Err(ref error)) => error.clone(),
+			_ => return None,
+		};
+
+		Some(FailureReport {
+			error: error.into(),
+			phase: data.state,
+			failed_node: data.failed_node.clone().map(Into::into),
+			transitions: data.transitions.clone(),
+			diagnostics: diagnostics,
+			partial_signature_latencies_ms: data.partial_arrival_order.iter()
+				.map(|&(ref node, ref arrived)| {
+					let latency = arrived.duration_since(data.consensus_started);
+					(node.clone().into(), latency.as_secs() * 1_000 + (latency.subsec_nanos() / 1_000_000) as u64)
+				})
+				.collect(),
+		})
+	}
+
 	/// Wait for session completion.
 	pub fn wait(&self) -> Result<(Secret, Secret), Error> {
-		Self::wait_session(&self.core.completed, &self.data, None, |data| data.result.clone())
+		self.wait_with_timeout(None)
+	}
+
+	/// Wait for session completion. Returns `Error::SessionTimeout` if session isn't completed within given timeout.
+	/// Session itself is not affected by the timeout.
+	pub fn wait_with_timeout(&self, timeout: Option<Duration>) -> Result<(Secret, Secret), Error> {
+		let started = Instant::now();
+		let result = Self::wait_session(&self.core.completed, &self.data, timeout, |data| data.result.clone());
+		self.data.lock().timing.network += started.elapsed();
+		result
+	}
+
+	/// Wait for session completion until given deadline. If session isn't completed by then, its progress is returned,
+	/// so that caller could decide whether to keep waiting or to give up.
+	pub fn wait_until(&self, deadline: Instant) -> Result<Result<(Secret, Secret), Error>, WaitTimeout> {
+		let mut data = self.data.lock();
+		loop {
+			if let Some(result) = data.result.clone() {
+				return Ok(result);
+			}
+
+			if Instant::now() >= deadline {
+				return Err(WaitTimeout {
+					state: data.state,
+					partial_signatures_count: data.consensus_session.computation_responses_count(),
+				});
+			}
+
+			let started = Instant::now();
+			self.core.completed.wait_until(&mut data, deadline);
+			data.timing.network += started.elapsed();
+		}
+	}
+
+	/// Get logical workload, this session belongs to.
+	pub fn workload(&self) -> Option<&str> {
+		self.core.workload.as_ref().map(|workload| workload.as_str())
+	}
+
+	/// Wait for completion of sessions, signing the same message hash under different key versions.
+	/// Signatures are returned keyed by key version.
+	pub fn wait_all_versions<'a, I>(sessions: I) -> Result<BTreeMap<H256, (Secret, Secret)>, Error> where I: IntoIterator<Item=&'a SessionImpl> {
+		let mut signatures = BTreeMap::new();
+		for session in sessions {
+			let signature = session.wait()?;
+			let version = session.data.lock().version.clone()
+				.ok_or(Error::InvalidStateForRequest)?;
+			signatures.insert(version, signature);
+		}
+		Ok(signatures)
+	}
+
+	/// Wait for session completion. If session has failed, error is returned together with failure context.
+	pub fn wait_detailed(&self) -> Result<(Secret, Secret), SigningError> {
+		self.wait().map_err(|error| {
+			let data = self.data.lock();
+			SigningError {
+				remediation: remediation_hint(&error, self.core.meta.threshold),
+				error: error,
+				phase: data.state,
+				node: data.failed_node.clone(),
+			}
+		})
+	}
+
+	/// Wait for session completion and return signature, encoded as DER SEQUENCE { c INTEGER, s INTEGER }.
+	pub fn wait_der(&self) -> Result<Vec<u8>, Error> {
+		self.wait_as::<DerSignature>().map(|signature| signature.0)
+	}
+
+	/// Get scheme of signature, computed by this session.
+	pub fn scheme(&self) -> SignatureScheme {
+		SignatureScheme::SchnorrSecp256k1
+	}
+
+	/// Wait for session completion and return signature, converted into given representation.
+	pub fn wait_as<T: FromSignature>(&self) -> Result<T, Error> {
+		self.wait().and_then(|(c, s)| T::from_signature(&c, &s))
 	}
 
 	/// Delegate session to other node.
@@ -233,21 +1106,137 @@ impl SessionImpl {
 			message_hash: message_hash.into(),
 		})))?;
 		data.delegation_status = Some(DelegationStatus::DelegatedTo(master));
+		data.message_hash = Some(message_hash);
 		Ok(())
 
 	}
 
-	/// Initialize signing session on master node.
+	/// Cancel session. If session is delegated to other node, delegate is asked to cancel it.
+	/// Otherwise (on master node) session is aborted on all consensus nodes.
+	pub fn cancel(&self) -> Result<(), Error> {
+		let mut data = self.data.lock();
+		if data.result.is_some() {
+			return Err(Error::InvalidStateForRequest);
+		}
+
+		match data.delegation_status.as_ref() {
+			Some(&DelegationStatus::DelegatedTo(ref delegate)) => {
+				self.core.cluster.send(delegate, Message::Signing(SigningMessage::SigningSessionDelegationCancel(SigningSessionDelegationCancel {
+					session: self.core.meta.id.clone().into(),
+					sub_session: self.core.access_key.clone().into(),
+					session_nonce: self.core.nonce,
+				})))?;
+			},
+			Some(&DelegationStatus::DelegatedFrom(_, _)) => return Err(Error::InvalidStateForRequest),
+			None if self.core.meta.self_node_id != self.core.meta.master_node_id => return Err(Error::InvalidStateForRequest),
+			None => {
+				// abort session on all non-rejected consensus nodes
+				// error means can't communicate => ignore it
+				for node in data.consensus_session.consensus_non_rejected_nodes() {
+					let _ = self.core.cluster.send(&node, Message::Signing(SigningMessage::SigningSessionError(SigningSessionError {
+						session: self.core.meta.id.clone().into(),
+						sub_session: self.core.access_key.clone().into(),
+						session_nonce: self.core.nonce,
+						error: Error::Cancelled.into(),
+					})));
+				}
+			},
+		}
+
+		Self::set_signing_result(&self.core, &mut *data, Err(Error::Cancelled));
+		Ok(())
+	}
+
+	/// Initialize signing session on master node. Message hash must be 32-byte keccak digest of the message.
 	pub fn initialize(&self, version: H256, message_hash: H256) -> Result<(), Error> {
+		// zero hash is never a digest of real message => it is a bug on the caller side
+		if message_hash.is_zero() {
+			return Err(Error::InvalidMessage);
+		}
+
+		if self.data.lock().require_canonical_message {
+			return Err(Error::InvalidMessage);
+		}
+
+		self.initialize_with_hash(version, message_hash)
+	}
+
+	/// Initialize signing session of canonically encoded structured message on master node.
+	pub fn initialize_structured(&self, version: H256, message: &[(String, Vec<u8>)]) -> Result<(), Error> {
+		let message_hash = canonical_message_hash(message)?;
+		self.initialize_with_hash(version, message_hash)
+	}
+
+	/// Initialize signing session of Merkle root of given leaves on master node. Returns the root, which is signed.
+	/// Inclusion proofs of leaves could be built (and checked) with `merkle_proof` (and `verify_merkle_proof`).
+	pub fn initialize_merkle(&self, version: H256, leaves: &[Vec<u8>]) -> Result<H256, Error> {
+		let root = merkle_root(leaves)?;
+		self.initialize(version, root.clone())?;
+		Ok(root)
+	}
+
+	/// Initialize signing session of given message hash on master node.
+	fn initialize_with_hash(&self, version: H256, message_hash: H256) -> Result<(), Error> {
 		debug_assert_eq!(self.core.meta.self_node_id, self.core.meta.master_node_id);
 
 		// check if version exists
 		let key_version = match self.core.key_share.as_ref() {
 			None => return Err(Error::InvalidMessage),
+			Some(key_share) if key_share.versions.is_empty() =>
+				return Err(Error::KeyStorage("key has no versions; run key generation first".into())),
 			Some(key_share) => key_share.version(&version).map_err(|e| Error::KeyStorage(e.into()))?,
 		};
 
 		let mut data = self.data.lock();
+
+		// session can only be initialized once
+		if data.state != SessionState::ConsensusEstablishing || data.version.is_some() {
+			return Err(Error::InvalidStateForRequest);
+		}
+
+		// new sessions are not started while node is drained for maintenance
+		if data.maintenance_mode.as_ref().map(|mode| mode.is_enabled()).unwrap_or(false) {
+			return Err(Error::NodeInMaintenance);
+		}
+
+		// request must be signed by the same requester, which has been authenticated by transport
+		if let Some((ref transport_identity, ref verifier)) = data.requester_identity {
+			let requester_signature = data.consensus_session.consensus_job().executor().requester_signature()
+				.ok_or(Error::InvalidStateForRequest)?;
+			let requester = ethkey::recover(requester_signature, &self.core.meta.id).map_err(|_| Error::AccessDenied)?;
+			if !verifier.verify(transport_identity, &ethkey::public_to_address(&requester)) {
+				warn!("{}: signing session {} requester does not match its transport identity {}",
+					&self.core.meta.self_node_id, &self.core.meta.id, transport_identity);
+				Self::report_denial(&self.core, &*data, DenialReason::TransportIdentityMismatch);
+				return Err(Error::AccessDenied);
+			}
+		}
+
+		data.consensus_started = Instant::now();
+		if let Some(metrics) = data.metrics.as_ref() {
+			metrics.on_session_started(self.workload());
+		}
+
+		// if this request has been recently served => return the same signature
+		// (but only if requester still has access to the key, otherwise run the protocol to deny the request)
+		let cached_signature = match data.signature_cache.as_ref() {
+			Some(cache) => {
+				let key_access_job = data.consensus_session.consensus_job().executor();
+				let requester = key_access_job.requester()?.ok_or(Error::InvalidStateForRequest)?;
+				match cache.get(&self.core.meta.id, &requester, &version, &message_hash) {
+					Some(cached_signature) if key_access_job.check_requester_access()? => Some(cached_signature),
+					_ => None,
+				}
+			},
+			None => None,
+		};
+		if let Some(cached_signature) = cached_signature {
+			data.version = Some(version);
+			data.message_hash = Some(message_hash);
+			Self::set_signing_result(&self.core, &mut *data, Ok(cached_signature));
+			return Ok(());
+		}
+
 		let non_isolated_nodes = self.core.cluster.nodes();
 		let mut consensus_nodes: BTreeSet<_> = key_version.id_numbers.keys()
 			.filter(|n| non_isolated_nodes.contains(*n))
@@ -258,16 +1247,27 @@ impl SessionImpl {
 			consensus_nodes.remove(&delegation_master);
 		}
 
+		// if there are not enough connected nodes => suggest client to retry later
+		let required_nodes_count = self.core.meta.threshold + 1;
+		if consensus_nodes.len() < required_nodes_count {
+			let missing_nodes_count = (required_nodes_count - consensus_nodes.len()) as u32;
+			return Err(Error::ConsensusUnreachable {
+				retry_after: Some(data.consensus_retry_backoff
+					.unwrap_or_else(|| Duration::from_secs(CONSENSUS_RETRY_BACKOFF)) * missing_nodes_count),
+			});
+		}
+
 		data.consensus_session.consensus_job_mut().transport_mut().version = Some(version.clone());
+		data.consensus_session.consensus_job_mut().executor_mut().set_version(version.clone());
 		data.version = Some(version.clone());
+		data.key_version = Some(key_version.clone());
 		data.message_hash = Some(message_hash);
 		data.consensus_session.initialize(consensus_nodes)?;
 
 		if data.consensus_session.state() == ConsensusSessionState::ConsensusEstablished {
-			let generation_session = GenerationSession::new(GenerationSessionParams {
+			let generation_session = GenerationSession::new_ephemeral(GenerationSessionParams {
 				id: self.core.meta.id.clone(),
 				self_node_id: self.core.meta.self_node_id.clone(),
-				key_storage: None,
 				cluster: Arc::new(SessionKeyGenerationTransport {
 					access_key: self.core.access_key.clone(),
 					cluster: self.core.cluster.clone(),
@@ -279,16 +1279,18 @@ impl SessionImpl {
 			generation_session.initialize(Public::default(), 0, vec![self.core.meta.self_node_id.clone()].into_iter().collect())?;
 
 			debug_assert_eq!(generation_session.state(), GenerationSessionState::WaitingForGenerationConfirmation);
-			let joint_public_and_secret = generation_session
-				.joint_public_and_secret()
-				.expect("session key is generated before signature is computed; we are in SignatureComputing state; qed")?;
+			let joint_public_and_secret = self.core.session_key(Some(&generation_session))?;
 			data.generation_session = Some(generation_session);
-			data.state = SessionState::SignatureComputing;
+			data.nonce_generation_nodes = vec![self.core.meta.self_node_id.clone()].into_iter().collect();
+			// session key is generated synchronously, but we still want to report the same lifecycle as on multi-node path
+			data.set_state(SessionState::SessionKeyGeneration);
+			data.set_state(SessionState::SignatureComputing);
 
-			self.core.disseminate_jobs(&mut data.consensus_session, &version, joint_public_and_secret.0, joint_public_and_secret.1, message_hash)?;
+			self.core.disseminate_jobs(&mut *data, &version, joint_public_and_secret.0, joint_public_and_secret.1, message_hash)?;
 
 			debug_assert!(data.consensus_session.state() == ConsensusSessionState::Finished);
 			let result = data.consensus_session.result()?;
+			Self::attest_signers(&self.core, &mut *data)?;
 			Self::set_signing_result(&self.core, &mut *data, Ok(result));
 		}
 
@@ -301,6 +1303,8 @@ impl SessionImpl {
 			return Err(Error::ReplayProtection);
 		}
 
+		self.check_lifetime()?;
+
 		match message {
 			&SigningMessage::SigningConsensusMessage(ref message) =>
 				self.on_consensus_message(sender, message),
@@ -318,6 +1322,8 @@ impl SessionImpl {
 				self.on_session_delegated(sender, message),
 			&SigningMessage::SigningSessionDelegationCompleted(ref message) =>
 				self.on_session_delegation_completed(sender, message),
+			&SigningMessage::SigningSessionDelegationCancel(ref message) =>
+				self.on_session_delegation_cancelled(sender, message),
 		}
 	}
 
@@ -326,6 +1332,10 @@ impl SessionImpl {
 		debug_assert!(self.core.meta.id == *message.session);
 		debug_assert!(self.core.access_key == *message.sub_session);
 
+		if message.message_hash.is_zero() {
+			return Err(Error::InvalidMessage);
+		}
+
 		{
 			let mut data = self.data.lock();
 			if data.consensus_session.state() != ConsensusSessionState::WaitingForInitialization || data.delegation_status.is_some() {
@@ -354,61 +1364,149 @@ impl SessionImpl {
 			_ => return Err(Error::InvalidMessage),
 		}
 
+		// delegate must sign exactly the same message hash, which has been requested
+		// (older nodes are not reporting signed message hash => nothing to check)
+		let is_other_message = match message.message_hash.as_ref() {
+			Some(message_hash) => data.message_hash.as_ref() != Some(&**message_hash),
+			None => false,
+		};
+		if is_other_message {
+			warn!("{}: delegated signing session has been completed with signature of other message", &self.core.meta.self_node_id);
+			Self::set_signing_result(&self.core, &mut *data, Err(Error::InvalidMessage));
+			return Err(Error::InvalidMessage);
+		}
+
 		Self::set_signing_result(&self.core, &mut *data, Ok((message.signature_c.clone().into(), message.signature_s.clone().into())));
 
 		Ok(())
 	}
 
-	/// When consensus-related message is received.
-	pub fn on_consensus_message(&self, sender: &NodeId, message: &SigningConsensusMessage) -> Result<(), Error> {
+	/// When delegated session is cancelled by delegating node (or when delegate acknowledges cancellation).
+	pub fn on_session_delegation_cancelled(&self, sender: &NodeId, message: &SigningSessionDelegationCancel) -> Result<(), Error> {
 		debug_assert!(self.core.meta.id == *message.session);
 		debug_assert!(self.core.access_key == *message.sub_session);
-		debug_assert!(sender != &self.core.meta.self_node_id);
 
 		let mut data = self.data.lock();
-		let is_establishing_consensus = data.consensus_session.state() == ConsensusSessionState::EstablishingConsensus;
-
-		if let &ConsensusMessage::InitializeConsensusSession(ref msg) = &message.message {
-			let version = msg.version.clone().into();
-			let has_key_share = self.core.key_share.as_ref()
-				.map(|ks| ks.version(&version).is_ok())
-				.unwrap_or(false);
-			data.consensus_session.consensus_job_mut().executor_mut().set_has_key_share(has_key_share);
-			data.version = Some(version);
-		}
-		data.consensus_session.on_consensus_message(&sender, &message.message)?;
+		let is_acknowledgement = match data.delegation_status.as_ref() {
+			Some(&DelegationStatus::DelegatedFrom(ref node, _)) if node == sender => false,
+			Some(&DelegationStatus::DelegatedTo(ref node)) if node == sender => true,
+			_ => return Err(Error::InvalidMessage),
+		};
 
-		let is_consensus_established = data.consensus_session.state() == ConsensusSessionState::ConsensusEstablished;
-		if self.core.meta.self_node_id != self.core.meta.master_node_id || !is_establishing_consensus || !is_consensus_established {
+		// delegate has acknowledged cancellation
+		if is_acknowledgement {
+			data.delegation_status = None;
+			if data.result.is_none() {
+				Self::set_signing_result(&self.core, &mut *data, Err(Error::Cancelled));
+			}
 			return Ok(());
 		}
 
-		let consensus_group = data.consensus_session.select_consensus_group()?.clone();
-		let mut other_consensus_group_nodes = consensus_group.clone();
-		other_consensus_group_nodes.remove(&self.core.meta.self_node_id);
+		// abort session on all other consensus nodes
+		// error means can't communicate => ignore it
+		for node in data.consensus_session.consensus_non_rejected_nodes() {
+			let _ = self.core.cluster.send(&node, Message::Signing(SigningMessage::SigningSessionError(SigningSessionError {
+				session: self.core.meta.id.clone().into(),
+				sub_session: self.core.access_key.clone().into(),
+				session_nonce: self.core.nonce,
+				error: Error::Cancelled.into(),
+			})));
+		}
 
-		let key_share = match self.core.key_share.as_ref() {
-			None => return Err(Error::InvalidMessage),
-			Some(key_share) => key_share,
+		// acknowledge cancellation to the delegating node
+		// error means can't communicate => ignore it
+		if let Some(DelegationStatus::DelegatedFrom(master, nonce)) = data.delegation_status.take() {
+			let _ = self.core.cluster.send(&master, Message::Signing(SigningMessage::SigningSessionDelegationCancel(SigningSessionDelegationCancel {
+				session: self.core.meta.id.clone().into(),
+				sub_session: self.core.access_key.clone().into(),
+				session_nonce: nonce,
+			})));
+		}
+
+		Self::set_signing_result(&self.core, &mut *data, Err(Error::Cancelled));
+		Ok(())
+	}
+
+	/// When consensus-related message is received.
+	pub fn on_consensus_message(&self, sender: &NodeId, message: &SigningConsensusMessage) -> Result<(), Error> {
+		debug_assert!(self.core.meta.id == *message.session);
+		debug_assert!(self.core.access_key == *message.sub_session);
+		debug_assert!(sender != &self.core.meta.self_node_id);
+
+		// master could start session key generation when consensus is established => wait for computation
+		// slot before locking session data, so that other messages of this session are not blocked
+		let (key_limiter, limiter) = match self.core.meta.self_node_id == self.core.meta.master_node_id {
+			true => self.key_generation_limiters(),
+			false => (None, None),
 		};
+		let _key_permit = key_limiter.as_ref().map(|limiter| limiter.acquire(&self.core.meta.id));
+		let _permit = limiter.as_ref().map(|limiter| limiter.acquire());
+		let mut data = self.data.lock();
+		let is_establishing_consensus = data.consensus_session.state() == ConsensusSessionState::EstablishingConsensus;
 
-		let generation_session = GenerationSession::new(GenerationSessionParams {
-			id: self.core.meta.id.clone(),
-			self_node_id: self.core.meta.self_node_id.clone(),
-			key_storage: None,
-			cluster: Arc::new(SessionKeyGenerationTransport {
-				access_key: self.core.access_key.clone(),
-				cluster: self.core.cluster.clone(),
-				nonce: self.core.nonce,
-				other_nodes_ids: other_consensus_group_nodes,
-			}),
-			nonce: None,
-		});
-		generation_session.initialize(Public::default(), key_share.threshold, consensus_group)?;
-		data.generation_session = Some(generation_session);
-		data.state = SessionState::SessionKeyGeneration;
+		if let ConsensusMessage::ConfirmConsensusInitialization(ref message) = message.message {
+			// confirmation must be signed by the node it is coming from
+			Self::check_confirmation_signature(&self.core, &*data, sender, message)?;
+
+			// if mandatory node has rejected consensus => session fails, no matter how many other nodes have confirmed
+			if !message.is_confirmed && self.core.mandatory_nodes.contains(sender) && data.result.is_none() {
+				Self::report_denial(&self.core, &*data, DenialReason::RejectedByMandatoryNode(sender.clone()));
+				Self::set_signing_result(&self.core, &mut *data, Err(Error::AccessDenied));
+
+				// abort session on all other nodes, so that they do not wait until timeout
+				// error means can't communicate => ignore it
+				let _ = self.core.cluster.broadcast(Message::Signing(SigningMessage::SigningSessionError(SigningSessionError {
+					session: self.core.meta.id.clone().into(),
+					sub_session: self.core.access_key.clone().into(),
+					session_nonce: self.core.nonce,
+					error: Error::AccessDenied.into(),
+				})));
+				return Err(Error::AccessDenied);
+			}
+		}
 
-		Ok(())
+		if let &ConsensusMessage::InitializeConsensusSession(ref msg) = &message.message {
+			let version = msg.version.clone().into();
+			let key_version = self.core.key_share.as_ref()
+				.and_then(|ks| ks.version(&version).ok())
+				.cloned();
+			let has_key_share = key_version.is_some();
+			// if we can't meet session deadline => decline participation
+			let can_meet_deadline = msg.deadline.map(|deadline| unix_millis_now() < deadline).unwrap_or(true);
+			if !can_meet_deadline {
+				warn!("{}: declining signing session with passed deadline", &self.core.meta.self_node_id);
+			}
+			data.consensus_session.consensus_job_mut().executor_mut().set_has_key_share(has_key_share && can_meet_deadline);
+			data.consensus_session.consensus_job_mut().executor_mut().set_version(version.clone());
+			data.version = Some(version);
+			data.key_version = key_version;
+			data.deadline = msg.deadline;
+		}
+		data.consensus_session.on_consensus_message(&sender, &message.message)?;
+
+		// if consensus group has been already selected, node has confirmed its participation too late
+		// => exclude it from consensus && let it know that it won't participate in signing
+		// (unless master is still waiting for all invited nodes to respond)
+		let is_confirmation = match message.message {
+			ConsensusMessage::ConfirmConsensusInitialization(ref message) => message.is_confirmed,
+			_ => false,
+		};
+		let is_deferring_key_generation = Self::is_deferring_key_generation(&self.core, &*data);
+		if self.core.meta.self_node_id == self.core.meta.master_node_id && !is_establishing_consensus && is_confirmation && !is_deferring_key_generation {
+			return self.core.exclude_late_consensus_node(&mut data.consensus_session, sender);
+		}
+
+		let is_consensus_established = data.consensus_session.state() == ConsensusSessionState::ConsensusEstablished;
+		if self.core.meta.self_node_id != self.core.meta.master_node_id || !(is_establishing_consensus || is_deferring_key_generation) || !is_consensus_established {
+			return Ok(());
+		}
+
+		// if master waits for all invited nodes => do not select consensus group until they respond
+		if !Self::is_key_generation_ready(&mut *data) {
+			return Ok(());
+		}
+
+		Self::start_key_generation(&self.core, &mut *data)
 	}
 
 	/// When session key related message is received.
@@ -417,6 +1515,10 @@ impl SessionImpl {
 		debug_assert!(self.core.access_key == *message.sub_session);
 		debug_assert!(sender != &self.core.meta.self_node_id);
 
+		// wait for computation slot before locking session data, so that other messages of this session are not blocked
+		let (key_limiter, limiter) = self.key_generation_limiters();
+		let _key_permit = key_limiter.as_ref().map(|limiter| limiter.acquire(&self.core.meta.id));
+		let _permit = limiter.as_ref().map(|limiter| limiter.acquire());
 		let mut data = self.data.lock();
 
 		if let &GenerationMessage::InitializeSession(ref message) = &message.message {
@@ -428,13 +1530,11 @@ impl SessionImpl {
 			}
 
 			let consensus_group: BTreeSet<NodeId> = message.nodes.keys().cloned().map(Into::into).collect();
-			let mut other_consensus_group_nodes = consensus_group.clone();
-			other_consensus_group_nodes.remove(&self.core.meta.self_node_id);
+			let other_consensus_group_nodes = self.core.other_consensus_group_nodes(&consensus_group)?;
 
-			let generation_session = GenerationSession::new(GenerationSessionParams {
+			let generation_session = GenerationSession::new_ephemeral(GenerationSessionParams {
 				id: self.core.meta.id.clone(),
 				self_node_id: self.core.meta.self_node_id.clone(),
-				key_storage: None,
 				cluster: Arc::new(SessionKeyGenerationTransport {
 					access_key: self.core.access_key.clone(),
 					cluster: self.core.cluster.clone(),
@@ -444,7 +1544,7 @@ impl SessionImpl {
 				nonce: None,
 			});
 			data.generation_session = Some(generation_session);
-			data.state = SessionState::SessionKeyGeneration;
+			data.set_state(SessionState::SessionKeyGeneration);
 		}
 
 		{
@@ -458,7 +1558,8 @@ impl SessionImpl {
 			}
 		}
 
-		data.state = SessionState::SignatureComputing;
+		data.set_state(SessionState::SignatureComputing);
+		Self::check_key_generation_duration(&self.core, &*data);
 		if self.core.meta.master_node_id != self.core.meta.self_node_id {
 			return Ok(());
 		}
@@ -466,11 +1567,8 @@ impl SessionImpl {
 		let version = data.version.as_ref().ok_or(Error::InvalidMessage)?.clone();
 		let message_hash = data.message_hash
 			.expect("we are on master node; on master node message_hash is filled in initialize(); on_generation_message follows initialize; qed");
-		let joint_public_and_secret = data.generation_session.as_ref()
-			.expect("session key is generated before signature is computed; we are in SignatureComputing state; qed")
-			.joint_public_and_secret()
-			.expect("session key is generated before signature is computed; we are in SignatureComputing state; qed")?;
-		self.core.disseminate_jobs(&mut data.consensus_session, &version, joint_public_and_secret.0, joint_public_and_secret.1, message_hash)
+		let joint_public_and_secret = self.core.session_key(data.generation_session.as_ref())?;
+		self.core.disseminate_jobs(&mut *data, &version, joint_public_and_secret.0, joint_public_and_secret.1, message_hash)
 	}
 
 	/// When partial signature is requested.
@@ -489,17 +1587,22 @@ impl SessionImpl {
 		if sender != &self.core.meta.master_node_id {
 			return Err(Error::InvalidMessage);
 		}
+		// master could request partial signature before this node has completed session key generation
+		// => request will be processed again when generation is completed
+		let is_generating_session_key = data.state == SessionState::SessionKeyGeneration
+			&& data.generation_session.as_ref().map(|s| s.state() != GenerationSessionState::Failed).unwrap_or(false);
+		if is_generating_session_key {
+			return Err(Error::TooEarlyForRequest);
+		}
 		if data.state != SessionState::SignatureComputing {
 			return Err(Error::InvalidStateForRequest);
 		}
 
-		let joint_public_and_secret = data.generation_session.as_ref()
-			.expect("session key is generated before signature is computed; we are in SignatureComputing state; qed")
-			.joint_public_and_secret()
-			.expect("session key is generated before signature is computed; we are in SignatureComputing state; qed")?;
-		let key_version = key_share.version(data.version.as_ref().ok_or(Error::InvalidMessage)?)
-			.map_err(|e| Error::KeyStorage(e.into()))?.hash.clone();
-		let signing_job = SigningJob::new_on_slave(self.core.meta.self_node_id.clone(), key_share.clone(), key_version, joint_public_and_secret.0, joint_public_and_secret.1)?;
+		let joint_public_and_secret = self.core.session_key(data.generation_session.as_ref())?;
+		let key_version = data.key_version.clone().ok_or(Error::InvalidMessage)?;
+		let mut signing_job = SigningJob::new_on_slave(self.core.meta.self_node_id.clone(), key_share.clone(), key_version, joint_public_and_secret.0, joint_public_and_secret.1)?;
+		signing_job.set_partial_signature_blinding(data.partial_signature_blinding);
+		signing_job.set_math_backend(self.core.timed_math_backend(&*data));
 		let signing_transport = self.core.signing_transport();
 
 		data.consensus_session.on_job_request(sender, PartialSigningRequest {
@@ -520,23 +1623,41 @@ impl SessionImpl {
 			request_id: message.request_id.clone().into(),
 			partial_signature: message.partial_signature.clone().into(),
 		})?;
+		let arrived = Instant::now();
+		if let Some(node_health) = data.consensus_session.node_health_tracker() {
+			node_health.record_success(sender, arrived.duration_since(data.consensus_started));
+		}
+		data.partial_arrival_order.push((sender.clone(), arrived));
 
 		if data.consensus_session.state() != ConsensusSessionState::Finished {
 			return Ok(());
 		}
 
-		// send compeltion signal to all nodes, except for rejected nodes
+		let result = data.consensus_session.result()?;
+
+		// if any of partial signatures was invalid, aggregated signature is invalid too
+		// => do not return it && do not report completion to other nodes
+		if let Err(err) = Self::check_signature(&self.core, &*data, &result) {
+			warn!("{}: signing session {} has computed invalid signature", &self.core.meta.self_node_id, &self.core.meta.id);
+			Self::set_signing_result(&self.core, &mut *data, Err(err.clone()));
+			return Err(err);
+		}
+
+		Self::attest_signers(&self.core, &mut *data)?;
+		Self::set_signing_result(&self.core, &mut *data, Ok(result));
+
+		// send completion signal to all nodes, except for rejected nodes
+		// signature is already computed => failing to notify some nodes is not an error
 		for node in data.consensus_session.consensus_non_rejected_nodes() {
-			self.core.cluster.send(&node, Message::Signing(SigningMessage::SigningSessionCompleted(SigningSessionCompleted {
+			if let Err(error) = self.core.cluster.send(&node, Message::Signing(SigningMessage::SigningSessionCompleted(SigningSessionCompleted {
 				session: self.core.meta.id.clone().into(),
 				sub_session: self.core.access_key.clone().into(),
 				session_nonce: self.core.nonce,
-			})))?;
+			}))) {
+				warn!("{}: failed to notify {} on signing session completion: {}", &self.core.meta.self_node_id, node, error);
+			}
 		}
 
-		let result = data.consensus_session.result()?;
-		Self::set_signing_result(&self.core, &mut *data, Ok(result));
-
 		Ok(())
 	}
 
@@ -549,16 +1670,74 @@ impl SessionImpl {
 		self.data.lock().consensus_session.on_session_completed(sender)
 	}
 
+	/// Fail session if it has not been completed within its lifetime.
+	fn check_lifetime(&self) -> Result<(), Error> {
+		let is_lifetime_exceeded = self.core.max_lifetime
+			.map(|max_lifetime| self.core.created.elapsed() > max_lifetime)
+			.unwrap_or(false);
+
+		let mut data = self.data.lock();
+		let is_deadline_passed = data.deadline
+			.map(|deadline| unix_millis_now() >= deadline)
+			.unwrap_or(false);
+		if (!is_lifetime_exceeded && !is_deadline_passed) || data.result.is_some() {
+			return Ok(());
+		}
+
+		warn!("{}: signing session has not been completed in time", &self.core.meta.self_node_id);
+		Self::set_signing_result(&self.core, &mut *data, Err(Error::SessionTimeout));
+		Err(Error::SessionTimeout)
+	}
+
 	/// Process error from the other node.
 	fn process_node_error(&self, node: Option<&NodeId>, error: Error) -> Result<(), Error> {
+		// master could start deferred session key generation => wait for computation slot before locking session data
+		let (key_limiter, limiter) = match self.core.meta.self_node_id == self.core.meta.master_node_id {
+			true => self.key_generation_limiters(),
+			false => (None, None),
+		};
+		let _key_permit = key_limiter.as_ref().map(|limiter| limiter.acquire(&self.core.meta.id));
+		let _permit = limiter.as_ref().map(|limiter| limiter.acquire());
 		let mut data = self.data.lock();
+		// errors from other nodes (including cancellation acknowledgement) are ignored once session is cancelled
+		if let Some(Err(Error::Cancelled)) = data.result {
+			return Ok(());
+		}
+
 		let is_self_node_error = node.map(|n| n == &self.core.meta.self_node_id).unwrap_or(false);
+		if !is_self_node_error && data.result.is_none() {
+			data.failed_node = node.cloned();
+			if let (Some(node), Some(node_health)) = (node, data.consensus_session.node_health_tracker()) {
+				node_health.record_failure(node);
+			}
+		}
 		// error is always fatal if coming from this node
 		if is_self_node_error {
 			Self::set_signing_result(&self.core, &mut *data, Err(error.clone()));
 			return Err(error);
 		}
 
+		// session key generation is unable to complete without this node => fail fast instead of waiting for timeout
+		if let Some(node) = node {
+			if let Err(err) = Self::check_live_nonce_generation_group(&self.core, &*data, node) {
+				warn!("{}: signing session failed with error: {:?} from {:?}", &self.core.meta.self_node_id, error, node);
+				Self::set_signing_result(&self.core, &mut *data, Err(err.clone()));
+				return Err(err);
+			}
+		}
+
+		// on session timeout, master stops waiting for remaining invited nodes && continues with confirmed ones
+		if node.is_none() && Self::is_deferring_key_generation(&self.core, &*data) {
+			return match Self::start_key_generation(&self.core, &mut *data) {
+				Ok(()) => Ok(()),
+				Err(err) => {
+					warn!("{}: signing session failed with error: {:?}", &self.core.meta.self_node_id, err);
+					Self::set_signing_result(&self.core, &mut *data, Err(err.clone()));
+					Err(err)
+				},
+			};
+		}
+
 		match {
 			match node {
 				Some(node) => data.consensus_session.on_node_error(node),
@@ -566,17 +1745,23 @@ impl SessionImpl {
 			}
 		} {
 			Ok(false) => {
+				// failed node could be the last one, master has been waiting for
+				if Self::is_deferring_key_generation(&self.core, &*data) && Self::is_key_generation_ready(&mut *data) {
+					if let Err(err) = Self::start_key_generation(&self.core, &mut *data) {
+						warn!("{}: signing session failed with error: {:?} from {:?}", &self.core.meta.self_node_id, error, node);
+						Self::set_signing_result(&self.core, &mut *data, Err(err.clone()));
+						return Err(err);
+					}
+				}
+
 				Ok(())
 			},
 			Ok(true) => {
 				let version = data.version.as_ref().ok_or(Error::InvalidMessage)?.clone();
 				let message_hash = data.message_hash.as_ref().cloned()
 					.expect("on_node_error returned true; this means that jobs must be REsent; this means that jobs already have been sent; jobs are sent when message_hash.is_some(); qed");
-				let joint_public_and_secret = data.generation_session.as_ref()
-					.expect("on_node_error returned true; this means that jobs must be REsent; this means that jobs already have been sent; jobs are sent when message_hash.is_some(); qed")
-					.joint_public_and_secret()
-					.expect("on_node_error returned true; this means that jobs must be REsent; this means that jobs already have been sent; jobs are sent when message_hash.is_some(); qed")?;
-				let disseminate_result = self.core.disseminate_jobs(&mut data.consensus_session, &version, joint_public_and_secret.0, joint_public_and_secret.1, message_hash);
+				let joint_public_and_secret = self.core.session_key(data.generation_session.as_ref())?;
+				let disseminate_result = self.core.disseminate_jobs(&mut *data, &version, joint_public_and_secret.0, joint_public_and_secret.1, message_hash);
 				match disseminate_result {
 					Ok(()) => Ok(()),
 					Err(err) => {
@@ -594,6 +1779,158 @@ impl SessionImpl {
 		}
 	}
 
+	/// Get limiters of session key generation computations. Permits must be acquired before locking session data,
+	/// otherwise all other messages of the session (and sessions for the same key) are waiting for the slot.
+	fn key_generation_limiters(&self) -> (Option<Arc<PerKeyComputationLimiter>>, Option<Arc<ComputationLimiter>>) {
+		let data = self.data.lock();
+		(data.key_generation_per_key_limiter.clone(), data.key_generation_limiter.clone())
+	}
+
+	/// Check if master node has deferred session key generation until all invited nodes respond.
+	fn is_deferring_key_generation(core: &SessionCore, data: &SessionData) -> bool {
+		core.meta.self_node_id == core.meta.master_node_id
+			&& data.full_consensus_wait.is_some()
+			&& data.state == SessionState::ConsensusEstablishing
+			&& data.consensus_session.state() == ConsensusSessionState::ConsensusEstablished
+	}
+
+	/// Check if session key generation could be started on master node, once consensus is established.
+	fn is_key_generation_ready(data: &mut SessionData) -> bool {
+		let full_consensus_wait = match data.full_consensus_wait {
+			Some(full_consensus_wait) => full_consensus_wait,
+			None => return true,
+		};
+		if data.consensus_session.consensus_job().requests().is_empty() {
+			return true;
+		}
+
+		let consensus_established = *data.consensus_established.get_or_insert_with(Instant::now);
+		consensus_established.elapsed() >= full_consensus_wait
+	}
+
+	/// Select consensus group and start session key generation on master node.
+	fn start_key_generation(core: &SessionCore, data: &mut SessionData) -> Result<(), Error> {
+		// version is required to compute signature => do not start session key generation without it
+		if data.version.is_none() {
+			return Err(Error::InvalidStateForRequest);
+		}
+
+		let consensus_group = data.consensus_session.select_consensus_group()?.clone();
+		if let Some(organizations_policy) = data.organizations_policy.as_ref() {
+			organizations_policy.check(&consensus_group)?;
+		}
+
+		let other_consensus_group_nodes = core.other_consensus_group_nodes(&consensus_group)?;
+
+		let key_share = match core.key_share.as_ref() {
+			None => return Err(Error::InvalidMessage),
+			Some(key_share) => key_share,
+		};
+
+		let generation_session = GenerationSession::new_ephemeral(GenerationSessionParams {
+			id: core.meta.id.clone(),
+			self_node_id: core.meta.self_node_id.clone(),
+			cluster: Arc::new(SessionKeyGenerationTransport {
+				access_key: core.access_key.clone(),
+				cluster: core.cluster.clone(),
+				nonce: core.nonce,
+				other_nodes_ids: other_consensus_group_nodes,
+			}),
+			nonce: None,
+		});
+		// computation slots are acquired by the caller, before locking session data
+		generation_session.initialize(Public::default(), key_share.threshold, consensus_group.clone())?;
+		data.generation_session = Some(generation_session);
+		data.nonce_generation_nodes = consensus_group;
+		data.set_state(SessionState::SessionKeyGeneration);
+
+		Ok(())
+	}
+
+	/// Check that aggregated signature is valid for the joint public key && the message hash.
+	fn check_signature(core: &SessionCore, data: &SessionData, signature: &(Secret, Secret)) -> Result<(), Error> {
+		let key_share = core.key_share.as_ref().ok_or(Error::InvalidMessage)?;
+		let message_hash = data.message_hash.as_ref().ok_or(Error::InvalidMessage)?;
+		match math::verify_signature(&key_share.public, signature, message_hash) {
+			Ok(true) => Ok(()),
+			_ => Err(Error::InvalidMessage),
+		}
+	}
+
+	/// Check signature of consensus confirmation, received from given node.
+	fn check_confirmation_signature(core: &SessionCore, data: &SessionData, sender: &NodeId, message: &ConfirmConsensusInitialization) -> Result<(), Error> {
+		match message.signature.as_ref() {
+			Some(signature) => {
+				let hash = confirmation_hash(&core.meta.id, &core.access_key, message.is_confirmed);
+				match ethkey::verify_public(sender, signature, &hash) {
+					Ok(true) => Ok(()),
+					_ => {
+						warn!("{}: signing session {} has received confirmation with invalid signature from {}",
+							&core.meta.self_node_id, &core.meta.id, sender);
+						Err(Error::InvalidMessage)
+					},
+				}
+			},
+			None if data.require_signed_confirmations => Err(Error::InvalidMessage),
+			None => Ok(()),
+		}
+	}
+
+	/// Check if session key generation is still able to complete when given node has failed.
+	fn check_live_nonce_generation_group(core: &SessionCore, data: &SessionData, failed_node: &NodeId) -> Result<(), Error> {
+		if data.state != SessionState::SessionKeyGeneration || !data.nonce_generation_nodes.contains(failed_node) {
+			return Ok(());
+		}
+
+		let threshold = match core.key_share.as_ref() {
+			Some(key_share) => key_share.threshold,
+			None => return Ok(()),
+		};
+		let live_nodes_count = data.nonce_generation_nodes.len() - 1;
+		if live_nodes_count < threshold + 1 {
+			return Err(Error::ConsensusUnreachable { retry_after: None });
+		}
+
+		Ok(())
+	}
+
+	/// Report slow session key generation, if soft threshold is configured and exceeded.
+	fn check_key_generation_duration(core: &SessionCore, data: &SessionData) {
+		let threshold = match data.key_generation_soft_threshold {
+			Some(threshold) => threshold,
+			None => return,
+		};
+
+		let duration = Instant::now().duration_since(data.key_generation_started);
+		if duration <= threshold {
+			return;
+		}
+
+		warn!("{}: session key generation of signing session {} has taken {:?}, which exceeds {:?}",
+			&core.meta.self_node_id, &core.meta.id, duration, threshold);
+		if let Some(metrics) = data.metrics.as_ref() {
+			metrics.on_slow_session_key_generation(core.workload.as_ref().map(|workload| workload.as_str()), duration);
+		}
+	}
+
+	/// Sign attestation of nodes, which have computed partial signatures.
+	fn attest_signers(core: &SessionCore, data: &mut SessionData) -> Result<(), Error> {
+		let attestation_key_pair = match data.attestation_key_pair.as_ref() {
+			Some(attestation_key_pair) => attestation_key_pair.clone(),
+			None => return Ok(()),
+		};
+
+		let version = data.version.as_ref().ok_or(Error::InvalidStateForRequest)?;
+		let message_hash = data.message_hash.as_ref().ok_or(Error::InvalidStateForRequest)?;
+		let signers = data.consensus_session.computation_responders();
+		let signature = attestation_key_pair.sign(&attestation_hash(&core.meta.id, message_hash, version, &signers))?;
+		data.attestation = Some(SigningAttestation {
+			signers: signers,
+			signature: signature,
+		});
+		Ok(())
+	}
+
 	/// Set signing session result.
 	fn set_signing_result(core: &SessionCore, data: &mut SessionData, result: Result<(Secret, Secret), Error>) {
 		if let Some(DelegationStatus::DelegatedFrom(master, nonce)) = data.delegation_status.take() {
@@ -605,6 +1942,7 @@ impl SessionImpl {
 					session_nonce: nonce,
 					signature_c: signature.0.clone().into(),
 					signature_s: signature.1.clone().into(),
+					message_hash: data.message_hash.clone().map(Into::into),
 				}))),
 				Err(error) => core.cluster.send(&master, Message::Signing(SigningMessage::SigningSessionError(SigningSessionError {
 					session: core.meta.id.clone().into(),
@@ -615,9 +1953,226 @@ impl SessionImpl {
 			};
 		}
 
-		data.result = Some(result);
+		if let Ok(signature) = result.as_ref() {
+			let requester = data.consensus_session.consensus_job().executor().requester().ok().and_then(|requester| requester);
+			if let (Some(cache), Some(requester), Some(version), Some(message_hash)) = (data.signature_cache.as_ref(), requester, data.version.as_ref(), data.message_hash.as_ref()) {
+				cache.insert(core.meta.id.clone(), requester, version.clone(), message_hash.clone(), signature.clone());
+			}
+		}
+
+		// consensus is unreachable because some nodes have rejected access => report denial
+		let is_consensus_unreachable = match result.as_ref() {
+			Err(&Error::ConsensusUnreachable { .. }) => true,
+			_ => false,
+		};
+		if is_consensus_unreachable && core.meta.self_node_id == core.meta.master_node_id
+			&& data.consensus_session.consensus_job().state() != JobSessionState::Inactive {
+			let rejects = data.consensus_session.consensus_job().rejects().clone();
+			if !rejects.is_empty() {
+				Self::report_denial(core, data, DenialReason::Rejected(rejects));
+			}
+		}
+
+		if let Some(metrics) = data.metrics.as_ref() {
+			metrics.on_session_completed(core.workload.as_ref().map(|workload| workload.as_str()), result.is_ok(), core.created.elapsed());
+		}
+
+		data.result = Some(match data.error_mapper.as_ref() {
+			Some(error_mapper) => result.map_err(|error| error_mapper.map_error(error)),
+			None => result,
+		});
+		Self::write_event(core, data);
 		core.completed.notify_all();
 	}
+
+	/// Report denied signing request.
+	fn report_denial(core: &SessionCore, data: &SessionData, reason: DenialReason) {
+		let denial_listener = match data.denial_listener.as_ref() {
+			Some(denial_listener) => denial_listener,
+			None => return,
+		};
+
+		let requester = data.consensus_session.consensus_job().executor().requester().ok()
+			.and_then(|requester| requester)
+			.map(|requester| ethkey::public_to_address(&requester));
+		denial_listener.on_denied(&SigningDenial {
+			key_id: core.meta.id.clone(),
+			requester: requester,
+			reason: reason,
+		});
+	}
+
+	/// Write session outcome event.
+	fn write_event(core: &SessionCore, data: &SessionData) {
+		let event_writer = match data.event_writer.as_ref() {
+			Some(event_writer) => event_writer,
+			None => return,
+		};
+
+		let duration = core.created.elapsed();
+		let error = match data.result {
+			Some(Err(ref error)) => Some(error.clone().into()),
+			_ => None,
+		};
+		let event = SessionEvent {
+			session_id: core.meta.id.clone().into(),
+			outcome: if error.is_none() { "succeeded".into() } else { "failed".into() },
+			duration_ms: duration.as_secs() * 1_000 + (duration.subsec_nanos() / 1_000_000) as u64,
+			signers: data.consensus_session.computation_responders().into_iter().map(Into::into).collect(),
+			error: error,
+		};
+
+		let mut event_writer = event_writer.lock();
+		if let Err(error) = serde_json::to_writer(&mut *event_writer, &event).map_err(|e| e.to_string())
+			.and_then(|_| event_writer.write_all(b"\n").map_err(|e| e.to_string())) {
+			warn!("{}: failed to write signing session {} event: {}", &core.meta.self_node_id, &core.meta.id, error);
+		}
+	}
+}
+
+impl DenialListener for LogDenialListener {
+	fn on_denied(&self, denial: &SigningDenial) {
+		warn!(target: "secretstore", "signing request for key {:?} from {:?} has been denied: {:?}",
+			denial.key_id, denial.requester, denial.reason);
+	}
+}
+
+impl ComputationLimiter {
+	/// Create new limiter, allowing given number of concurrent computations.
+	pub fn new(max_concurrent: usize) -> Self {
+		ComputationLimiter {
+			max_concurrent: ::std::cmp::max(max_concurrent, 1),
+			active: Mutex::new(0),
+			completed: Condvar::new(),
+		}
+	}
+
+	/// Wait until computation slot is available and occupy it.
+	pub fn acquire(&self) -> ComputationPermit {
+		let mut active = self.active.lock();
+		while *active >= self.max_concurrent {
+			self.completed.wait(&mut active);
+		}
+		*active += 1;
+
+		ComputationPermit {
+			limiter: self,
+		}
+	}
+}
+
+impl<'a> Drop for ComputationPermit<'a> {
+	fn drop(&mut self) {
+		*self.limiter.active.lock() -= 1;
+		self.limiter.completed.notify_one();
+	}
+}
+
+impl PerKeyComputationLimiter {
+	/// Create new limiter, allowing given number of concurrent computations for every key.
+	pub fn new(max_concurrent_per_key: usize) -> Self {
+		PerKeyComputationLimiter {
+			max_concurrent_per_key: ::std::cmp::max(max_concurrent_per_key, 1),
+			active: Mutex::new(BTreeMap::new()),
+			completed: Condvar::new(),
+		}
+	}
+
+	/// Wait until computation slot for given key is available and occupy it.
+	pub fn acquire(&self, key_id: &SessionId) -> PerKeyComputationPermit {
+		let mut active = self.active.lock();
+		while active.get(key_id).cloned().unwrap_or(0) >= self.max_concurrent_per_key {
+			self.completed.wait(&mut active);
+		}
+		*active.entry(key_id.clone()).or_insert(0) += 1;
+
+		PerKeyComputationPermit {
+			limiter: self,
+			key_id: key_id.clone(),
+		}
+	}
+}
+
+impl<'a> Drop for PerKeyComputationPermit<'a> {
+	fn drop(&mut self) {
+		{
+			let mut active = self.limiter.active.lock();
+			let is_last = match active.get_mut(&self.key_id) {
+				Some(active) => {
+					*active -= 1;
+					*active == 0
+				},
+				None => false,
+			};
+			if is_last {
+				active.remove(&self.key_id);
+			}
+		}
+		// waiters may be waiting for different keys => wake all of them
+		self.limiter.completed.notify_all();
+	}
+}
+
+impl MaintenanceMode {
+	/// Enter or leave maintenance mode.
+	pub fn set_enabled(&self, enabled: bool) {
+		self.enabled.store(enabled, Ordering::SeqCst);
+	}
+
+	/// Is node in maintenance mode?
+	pub fn is_enabled(&self) -> bool {
+		self.enabled.load(Ordering::SeqCst)
+	}
+}
+
+impl SignatureCache {
+	/// Create new cache with given entries time to live.
+	pub fn new(ttl: Duration) -> Self {
+		SignatureCache {
+			ttl: ttl,
+			entries: Mutex::new(BTreeMap::new()),
+		}
+	}
+
+	/// Get signature, cached for given requester.
+	pub fn get(&self, id: &SessionId, requester: &Public, version: &H256, message_hash: &H256) -> Option<(Secret, Secret)> {
+		let mut entries = self.entries.lock();
+		let ttl = self.ttl;
+		entries.retain(|_, &mut (ref inserted, _)| inserted.elapsed() < ttl);
+		entries.get(&(id.clone(), requester.clone(), version.clone(), message_hash.clone())).map(|&(_, ref signature)| signature.clone())
+	}
+
+	/// Cache signature, computed for given requester.
+	pub fn insert(&self, id: SessionId, requester: Public, version: H256, message_hash: H256, signature: (Secret, Secret)) {
+		self.entries.lock().insert((id, requester, version, message_hash), (Instant::now(), signature));
+	}
+}
+
+impl OrganizationsPolicy {
+	/// Check that given nodes belong to enough distinct organizations.
+	pub fn check(&self, nodes: &BTreeSet<NodeId>) -> Result<(), Error> {
+		let orgs: BTreeSet<_> = nodes.iter().filter_map(|node| self.node_org.get(node)).collect();
+		if orgs.len() < self.min_orgs {
+			return Err(Error::ConsensusUnreachable { retry_after: None });
+		}
+
+		Ok(())
+	}
+}
+
+impl SessionData {
+	/// Move session to the given state.
+	fn set_state(&mut self, state: SessionState) {
+		if self.state == SessionState::ConsensusEstablishing && state != SessionState::ConsensusEstablishing {
+			self.timing.consensus = Instant::now().duration_since(self.consensus_started);
+		}
+		if state == SessionState::SessionKeyGeneration {
+			self.key_generation_started = Instant::now();
+		}
+
+		self.state = state;
+		self.transitions.push(state);
+	}
 }
 
 impl ClusterSession for SessionImpl {
@@ -674,11 +2229,40 @@ impl ClusterSession for SessionImpl {
 	fn on_message(&self, sender: &NodeId, message: &Message) -> Result<(), Error> {
 		match *message {
 			Message::Signing(ref message) => self.process_message(sender, message),
-			_ => unreachable!("cluster checks message to be correct before passing; qed"),
+			_ => Err(Error::InvalidMessage),
 		}
 	}
 }
 
+impl TimedMathBackend {
+	fn timed<T, F: FnOnce(&MathBackend) -> T>(&self, computation: F) -> T {
+		let started = Instant::now();
+		let result = computation(&*self.backend);
+		*self.compute_time.lock() += started.elapsed();
+		result
+	}
+}
+
+impl MathBackend for TimedMathBackend {
+	fn combine_message_hash_with_public(&self, message_hash: &H256, public: &Public) -> Result<Secret, Error> {
+		self.timed(|backend| backend.combine_message_hash_with_public(message_hash, public))
+	}
+
+	fn compute_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+		node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error> {
+		self.timed(|backend| backend.compute_signature_share(threshold, combined_hash, one_time_secret_coeff, node_secret_share, node_number, other_nodes_numbers))
+	}
+
+	fn compute_blinded_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+		node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error> {
+		self.timed(|backend| backend.compute_blinded_signature_share(threshold, combined_hash, one_time_secret_coeff, node_secret_share, node_number, other_nodes_numbers))
+	}
+
+	fn compute_signature(&self, signature_shares: &[&Secret]) -> Result<Secret, Error> {
+		self.timed(|backend| backend.compute_signature(signature_shares))
+	}
+}
+
 impl SessionKeyGenerationTransport {
 	fn map_message(&self, message: Message) -> Result<Message, Error> {
 		match message {
@@ -703,7 +2287,10 @@ impl Cluster for SessionKeyGenerationTransport {
 	}
 
 	fn send(&self, to: &NodeId, message: Message) -> Result<(), Error> {
-		debug_assert!(self.other_nodes_ids.contains(to));
+		if !self.other_nodes_ids.contains(to) {
+			return Err(Error::InvalidNodesConfiguration);
+		}
+
 		self.cluster.send(to, self.map_message(message)?)
 	}
 
@@ -717,6 +2304,28 @@ impl Cluster for SessionKeyGenerationTransport {
 }
 
 impl SessionCore {
+	/// Get other members of consensus group, which is generating session key. This node must be a member of the group.
+	fn other_consensus_group_nodes(&self, consensus_group: &BTreeSet<NodeId>) -> Result<BTreeSet<NodeId>, Error> {
+		if !consensus_group.contains(&self.meta.self_node_id) {
+			warn!("{}: signing session {} consensus group does not include this node", &self.meta.self_node_id, &self.meta.id);
+			return Err(Error::InvalidNodesConfiguration);
+		}
+
+		let mut other_consensus_group_nodes = consensus_group.clone();
+		other_consensus_group_nodes.remove(&self.meta.self_node_id);
+		Ok(other_consensus_group_nodes)
+	}
+
+	/// Get session key, generated by finished session key generation session.
+	fn session_key(&self, generation_session: Option<&GenerationSession>) -> Result<(Public, Secret), Error> {
+		generation_session
+			.and_then(|generation_session| generation_session.joint_public_and_secret())
+			.unwrap_or_else(|| {
+				warn!("{}: session key generation session of signing session {} has not generated session key", &self.meta.self_node_id, &self.meta.id);
+				Err(Error::InvalidStateForRequest)
+			})
+	}
+
 	pub fn signing_transport(&self) -> SigningJobTransport {
 		SigningJobTransport {
 			id: self.meta.id.clone(),
@@ -726,16 +2335,274 @@ impl SessionCore {
 		}
 	}
 
-	pub fn disseminate_jobs(&self, consensus_session: &mut SigningConsensusSession, version: &H256, session_public: Public, session_secret_share: Secret, message_hash: H256) -> Result<(), Error> {
-		let key_share = match self.key_share.as_ref() {
-			None => return Err(Error::InvalidMessage),
-			Some(key_share) => key_share,
+	/// Get math backend, which accounts computation time of this session.
+	fn timed_math_backend(&self, data: &SessionData) -> Arc<MathBackend> {
+		Arc::new(TimedMathBackend {
+			backend: data.math_backend.clone(),
+			compute_time: self.compute_time.clone(),
+		})
+	}
+
+	pub fn disseminate_jobs(&self, data: &mut SessionData, version: &H256, session_public: Public, session_secret_share: Secret, message_hash: H256) -> Result<(), Error> {
+		// partial signatures are only requested from (and accepted from) consensus group members
+		// => it is enough to check the group before jobs are sent
+		if let Some(organizations_policy) = data.organizations_policy.as_ref() {
+			organizations_policy.check(data.consensus_session.select_consensus_group()?)?;
+		}
+
+		let key_share = match self.key_share.as_ref() {
+			None => return Err(Error::InvalidMessage),
+			Some(key_share) => key_share,
 		};
 
-		let key_version = key_share.version(version).map_err(|e| Error::KeyStorage(e.into()))?.hash.clone();
-		let signing_job = SigningJob::new_on_master(self.meta.self_node_id.clone(), key_share.clone(), key_version, session_public, session_secret_share, message_hash)?;
-		consensus_session.disseminate_jobs(signing_job, self.signing_transport())
+		// use version, pinned at initialization, even if key share has been updated since then
+		let key_version = match data.key_version.as_ref() {
+			Some(key_version) if &key_version.hash == version => key_version.clone(),
+			_ => return Err(Error::InvalidMessage),
+		};
+		let mut signing_job = SigningJob::new_on_master(self.meta.self_node_id.clone(), key_share.clone(), key_version, session_public, session_secret_share, message_hash)?;
+		signing_job.set_partial_signature_blinding(data.partial_signature_blinding);
+		signing_job.set_math_backend(self.timed_math_backend(data));
+		data.consensus_session.disseminate_jobs(signing_job, self.signing_transport())
+	}
+
+	pub fn exclude_late_consensus_node(&self, consensus_session: &mut SigningConsensusSession, node: &NodeId) -> Result<(), Error> {
+		// node is not a part of selected consensus group => it won't be asked to sign && won't be notified on completion
+		consensus_session.consensus_job_mut().on_node_error(node)?;
+		self.cluster.send(node, Message::Signing(SigningMessage::SigningSessionCompleted(SigningSessionCompleted {
+			session: self.meta.id.clone().into(),
+			sub_session: self.access_key.clone().into(),
+			session_nonce: self.nonce,
+		})))
+	}
+}
+
+/// Get number of milliseconds since UNIX epoch.
+fn to_unix_millis(time: SystemTime) -> u64 {
+	time.duration_since(UNIX_EPOCH)
+		.map(|since_epoch| since_epoch.as_secs() * 1_000 + (since_epoch.subsec_nanos() / 1_000_000) as u64)
+		.unwrap_or_default()
+}
+
+/// Get current time as number of milliseconds since UNIX epoch.
+fn unix_millis_now() -> u64 {
+	to_unix_millis(SystemTime::now())
+}
+
+/// Get human-readable hint on how to resolve signing session error.
+fn remediation_hint(error: &Error, threshold: usize) -> String {
+	match *error {
+		Error::ConsensusUnreachable { .. } => format!("too few key servers have agreed to sign; ensure at least {} key servers are reachable and allow access to the key", threshold + 1),
+		Error::AccessDenied => "requester is not allowed to use this key; check the ACL contract".into(),
+		Error::NodeDisconnected => "connection to key server has been lost; check the network connectivity between key servers".into(),
+		Error::SessionTimeout => "session has not been completed in time; retry the request later".into(),
+		Error::MissingKeyShare | Error::KeyStorage(_) => "key (or its version) is not found on key server; check the key id".into(),
+		Error::InvalidMessage | Error::InvalidMessageVersion | Error::ReplayProtection =>
+			"protocol error; ensure that all key servers are running the same version".into(),
+		_ => "internal error; see key server logs for details".into(),
+	}
+}
+
+/// Compute hash of data, signed by signing attestation.
+pub fn attestation_hash(session_id: &SessionId, message_hash: &H256, version: &H256, signers: &BTreeSet<NodeId>) -> H256 {
+	let mut keccak = Keccak::new_keccak256();
+	keccak.update(&**session_id);
+	keccak.update(&**message_hash);
+	keccak.update(&**version);
+	for signer in signers {
+		keccak.update(&**signer);
+	}
+
+	let mut hash = [0u8; 32];
+	keccak.finalize(&mut hash);
+	hash.into()
+}
+
+/// Compute hash of data, signed by consensus confirmation.
+pub fn confirmation_hash(session_id: &SessionId, access_key: &Secret, is_confirmed: bool) -> H256 {
+	let mut keccak = Keccak::new_keccak256();
+	keccak.update(&**session_id);
+	keccak.update(&**access_key);
+	keccak.update(&[is_confirmed as u8]);
+
+	let mut hash = [0u8; 32];
+	keccak.finalize(&mut hash);
+	hash.into()
+}
+
+/// Compute hash of canonical encoding of structured message. Fields are ordered by name and
+/// every name and value is prefixed with its length, so field order and boundaries can't affect the hash.
+pub fn canonical_message_hash(message: &[(String, Vec<u8>)]) -> Result<H256, Error> {
+	let mut fields: Vec<_> = message.iter().collect();
+	fields.sort_by(|a, b| a.0.cmp(&b.0));
+	if fields.windows(2).any(|w| w[0].0 == w[1].0) {
+		return Err(Error::InvalidMessage);
+	}
+
+	let mut keccak = Keccak::new_keccak256();
+	for &&(ref name, ref value) in &fields {
+		keccak.update(&encode_length(name.len()));
+		keccak.update(name.as_bytes());
+		keccak.update(&encode_length(value.len()));
+		keccak.update(value);
+	}
+
+	let mut hash = [0u8; 32];
+	keccak.finalize(&mut hash);
+	Ok(hash.into())
+}
+
+/// Compute Merkle root of given leaves.
+/// Leaf hash is keccak256(0x00 || leaf) and inner node hash is keccak256(0x01 || left || right).
+/// When level has odd number of nodes, the last node is promoted to the next level unchanged.
+pub fn merkle_root(leaves: &[Vec<u8>]) -> Result<H256, Error> {
+	let mut level = merkle_leaves_hashes(leaves)?;
+	while level.len() > 1 {
+		level = merkle_next_level(&level);
+	}
+	Ok(level.pop().expect("level is never empty; qed"))
+}
+
+/// Build inclusion proof of leaf with given index in Merkle tree, built by `merkle_root`.
+pub fn merkle_proof(leaves: &[Vec<u8>], index: usize) -> Result<Vec<MerkleProofNode>, Error> {
+	let mut level = merkle_leaves_hashes(leaves)?;
+	if index >= level.len() {
+		return Err(Error::InvalidMessage);
+	}
+
+	let mut index = index;
+	let mut proof = Vec::new();
+	while level.len() > 1 {
+		let sibling_index = index ^ 1;
+		if sibling_index < level.len() {
+			proof.push(MerkleProofNode {
+				is_left: sibling_index < index,
+				hash: level[sibling_index].clone(),
+			});
+		}
+
+		level = merkle_next_level(&level);
+		index /= 2;
+	}
+	Ok(proof)
+}
+
+/// Check that leaf is included in Merkle tree with given root.
+pub fn verify_merkle_proof(root: &H256, leaf: &[u8], proof: &[MerkleProofNode]) -> bool {
+	let hash = proof.iter().fold(merkle_leaf_hash(leaf), |hash, node| match node.is_left {
+		true => merkle_node_hash(&node.hash, &hash),
+		false => merkle_node_hash(&hash, &node.hash),
+	});
+	&hash == root
+}
+
+/// Compute hashes of Merkle tree leaves.
+fn merkle_leaves_hashes(leaves: &[Vec<u8>]) -> Result<Vec<H256>, Error> {
+	if leaves.is_empty() {
+		return Err(Error::InvalidMessage);
+	}
+
+	Ok(leaves.iter().map(|leaf| merkle_leaf_hash(leaf)).collect())
+}
+
+/// Compute next level of Merkle tree.
+fn merkle_next_level(level: &[H256]) -> Vec<H256> {
+	level.chunks(2).map(|pair| match pair.len() {
+		2 => merkle_node_hash(&pair[0], &pair[1]),
+		_ => pair[0].clone(),
+	}).collect()
+}
+
+/// Compute hash of Merkle tree leaf.
+fn merkle_leaf_hash(leaf: &[u8]) -> H256 {
+	let mut keccak = Keccak::new_keccak256();
+	keccak.update(&[0u8]);
+	keccak.update(leaf);
+
+	let mut hash = [0u8; 32];
+	keccak.finalize(&mut hash);
+	hash.into()
+}
+
+/// Compute hash of Merkle tree inner node.
+fn merkle_node_hash(left: &H256, right: &H256) -> H256 {
+	let mut keccak = Keccak::new_keccak256();
+	keccak.update(&[1u8]);
+	keccak.update(&**left);
+	keccak.update(&**right);
+
+	let mut hash = [0u8; 32];
+	keccak.finalize(&mut hash);
+	hash.into()
+}
+
+/// Encode length as 8-byte big-endian integer.
+fn encode_length(len: usize) -> [u8; 8] {
+	let len = len as u64;
+	let mut encoded = [0u8; 8];
+	for (i, byte) in encoded.iter_mut().enumerate() {
+		*byte = (len >> (8 * (7 - i))) as u8;
+	}
+	encoded
+}
+
+impl FromSignature for (Secret, Secret) {
+	fn from_signature(c: &Secret, s: &Secret) -> Result<Self, Error> {
+		Ok((c.clone(), s.clone()))
+	}
+}
+
+impl FromSignature for TaggedSignature {
+	fn from_signature(c: &Secret, s: &Secret) -> Result<Self, Error> {
+		Ok(TaggedSignature {
+			scheme: SignatureScheme::SchnorrSecp256k1,
+			signature: (c.clone(), s.clone()),
+		})
+	}
+}
+
+impl FromSignature for DerSignature {
+	fn from_signature(c: &Secret, s: &Secret) -> Result<Self, Error> {
+		Ok(DerSignature(der_encode_signature(c, s)))
+	}
+}
+
+impl FromSignature for CompactSignature {
+	fn from_signature(c: &Secret, s: &Secret) -> Result<Self, Error> {
+		let mut encoded = [0u8; 64];
+		encoded[..32].copy_from_slice(&**c);
+		encoded[32..].copy_from_slice(&**s);
+		Ok(CompactSignature(encoded))
+	}
+}
+
+/// Encode signature as DER SEQUENCE of two INTEGERs.
+fn der_encode_signature(c: &[u8], s: &[u8]) -> Vec<u8> {
+	let mut content = der_encode_unsigned_integer(c);
+	content.extend(der_encode_unsigned_integer(s));
+
+	// both integers are at most 33 bytes long => short form of length is always used
+	let mut encoded = vec![0x30, content.len() as u8];
+	encoded.extend(content);
+	encoded
+}
+
+/// Encode big-endian unsigned integer as DER INTEGER.
+fn der_encode_unsigned_integer(value: &[u8]) -> Vec<u8> {
+	// leading zero bytes are not allowed, but at least one byte must be left
+	let leading_zeros = value.iter().take_while(|b| **b == 0).count();
+	let value = &value[::std::cmp::min(leading_zeros, value.len() - 1)..];
+
+	// if high bit is set, zero byte is required to keep the integer positive
+	let padding = if value[0] & 0x80 != 0 { 1 } else { 0 };
+	let mut encoded = Vec::with_capacity(2 + padding + value.len());
+	encoded.push(0x02);
+	encoded.push((padding + value.len()) as u8);
+	if padding != 0 {
+		encoded.push(0x00);
 	}
+	encoded.extend_from_slice(value);
+	encoded
 }
 
 impl JobTransport for SigningConsensusTransport {
@@ -752,6 +2619,7 @@ impl JobTransport for SigningConsensusTransport {
 			message: ConsensusMessage::InitializeConsensusSession(InitializeConsensusSession {
 				requestor_signature: request.into(),
 				version: version.clone().into(),
+				deadline: self.deadline,
 			})
 		})))
 	}
@@ -763,6 +2631,10 @@ impl JobTransport for SigningConsensusTransport {
 			session_nonce: self.nonce,
 			message: ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 				is_confirmed: response,
+				signature: match self.confirmation_key_pair.as_ref() {
+					Some(key_pair) => Some(key_pair.sign(&confirmation_hash(&self.id, &self.access_key, response))?.into()),
+					None => None,
+				},
 			})
 		})))
 	}
@@ -794,22 +2666,182 @@ impl JobTransport for SigningJobTransport {
 	}
 }
 
-#[cfg(test)]
-mod tests {
+/// Signing throughput benchmark.
+pub mod benchmark {
+	use std::collections::BTreeSet;
 	use std::sync::Arc;
-	use std::str::FromStr;
-	use std::collections::{BTreeMap, VecDeque};
+	use std::time::{Duration, Instant};
+	use ethkey::{self, Random, Generator};
 	use ethereum_types::H256;
-	use ethkey::{self, Random, Generator, Public, Secret, KeyPair};
 	use acl_storage::DummyAclStorage;
-	use key_server_cluster::{NodeId, DummyKeyStorage, DocumentKeyShare, DocumentKeyShareVersion, SessionId, SessionMeta, Error, KeyStorage};
+	use key_server_cluster::{Error, NodeId, SessionId, SessionMeta, DocumentKeyShare, DocumentKeyShareVersion};
+	use key_server_cluster::math;
+	use key_server_cluster::cluster::Cluster;
+	use key_server_cluster::message::Message;
+	use super::{SessionImpl, SessionParams};
+
+	/// Estimate signing throughput of this node (in signatures per second) by running single-node signing
+	/// sessions back-to-back for given duration. Sessions are using throwaway keys and isolated cluster.
+	pub fn benchmark_signing_throughput(duration: Duration) -> Result<f64, Error> {
+		let self_node_id = Random.generate()?.public().clone();
+		let requester = Random.generate()?;
+		let key_id = SessionId::default();
+		let requester_signature = ethkey::sign(requester.secret(), &key_id)?;
+
+		// with threshold = 0, every share of the key is the key itself
+		let key_secret = math::generate_random_scalar()?;
+		let key_version = DocumentKeyShareVersion::new(vec![(self_node_id.clone(), math::generate_random_scalar()?)].into_iter().collect(), key_secret.clone());
+		let version = key_version.hash.clone();
+		let key_share = DocumentKeyShare {
+			author: Default::default(),
+			threshold: 0,
+			public: ethkey::KeyPair::from_secret(key_secret)?.public().clone(),
+			common_point: None,
+			encrypted_point: None,
+			versions: vec![key_version],
+		};
+		let acl_storage = Arc::new(DummyAclStorage::default());
+		let cluster = Arc::new(IsolatedCluster { self_node_id: self_node_id.clone() });
+
+		let started = Instant::now();
+		let mut signatures_count = 0;
+		while started.elapsed() < duration {
+			let session = SessionImpl::new(SessionParams {
+				meta: SessionMeta {
+					id: key_id.clone(),
+					self_node_id: self_node_id.clone(),
+					master_node_id: self_node_id.clone(),
+					threshold: 0,
+				},
+				access_key: math::generate_random_scalar()?,
+				key_share: Some(key_share.clone()),
+				acl_storage: acl_storage.clone(),
+				cluster: cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: Default::default(),
+				options: Default::default(),
+			}, Some(requester_signature.clone()))?;
+			session.initialize(version.clone(), H256::random())?;
+			session.wait()?;
+			signatures_count += 1;
+		}
+
+		let elapsed = started.elapsed();
+		let elapsed = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1_000_000_000f64;
+		Ok(signatures_count as f64 / elapsed)
+	}
+
+	/// Cluster of single node, used to run benchmark sessions.
+	struct IsolatedCluster {
+		/// This node id.
+		self_node_id: NodeId,
+	}
+
+	impl Cluster for IsolatedCluster {
+		fn broadcast(&self, _message: Message) -> Result<(), Error> {
+			Ok(())
+		}
+
+		fn send(&self, _to: &NodeId, _message: Message) -> Result<(), Error> {
+			Err(Error::NodeDisconnected)
+		}
+
+		fn is_connected(&self, node: &NodeId) -> bool {
+			*node == self.self_node_id
+		}
+
+		fn nodes(&self) -> BTreeSet<NodeId> {
+			vec![self.self_node_id.clone()].into_iter().collect()
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::io::{self, Write};
+	use std::sync::{Arc, Barrier};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+	use parking_lot::Mutex;
+	use std::str::FromStr;
+	use std::thread;
+	use std::time::{Duration, Instant, SystemTime};
+	use std::collections::{BTreeSet, BTreeMap, VecDeque};
+	use rustc_hex::ToHex;
+	use serde_json;
+	use ethereum_types::{H256, Address};
+	use ethkey::{self, Random, Generator, Public, Secret, Signature, KeyPair};
+	use acl_storage::{AclStorage, AclStorageHandle, DummyAclStorage};
+	use types::all::Error as ServerError;
+	use key_server_cluster::{NodeId, PlainNodeKeyPair, DummyKeyStorage, DocumentKeyShare, DocumentKeyShareVersion, SessionId, SessionMeta, Error, KeyStorage,
+		SerializablePublic};
 	use key_server_cluster::cluster_sessions::ClusterSession;
+	use key_server_cluster::cluster::Cluster;
 	use key_server_cluster::cluster::tests::DummyCluster;
+	use key_server_cluster::jobs::consensus_session::{ConsensusSessionState, NodeHealthTracker};
+	use key_server_cluster::jobs::key_access_job::AccessDecisionsCache;
 	use key_server_cluster::generation_session::tests::MessageLoop as KeyGenerationMessageLoop;
-	use key_server_cluster::math;
+	use key_server_cluster::math::{self, MathBackend, DefaultMathBackend};
 	use key_server_cluster::message::{Message, SigningMessage, SigningConsensusMessage, ConsensusMessage, ConfirmConsensusInitialization,
-		SigningGenerationMessage, GenerationMessage, ConfirmInitialization, InitializeSession, RequestPartialSignature};
-	use key_server_cluster::signing_session::{SessionImpl, SessionState, SessionParams};
+		SigningGenerationMessage, GenerationMessage, ConfirmInitialization, InitializeSession, RequestPartialSignature,
+		ClusterMessage, KeepAlive, SigningSessionDelegation};
+	use key_server_cluster::signing_session::{benchmark, der_encode_signature, attestation_hash, confirmation_hash, FromSignature, CompactSignature,
+		TaggedSignature, SignatureScheme, ComputationLimiter, PerKeyComputationLimiter, RequesterIdentityVerifier, WaitTimeout,
+		MaintenanceMode, DenialListener, DenialReason, SigningDenial,
+		canonical_message_hash, merkle_root, merkle_proof, verify_merkle_proof, SigningMetrics,
+		PrometheusSessionMetrics, SessionImpl, SessionState, SessionParams, SigningSessionOptions, SigningRequestOptions, ErrorMapper, OrganizationsPolicy,
+		SignatureCache};
+
+	struct AccessDeniedErrorMapper;
+
+	#[derive(Default)]
+	struct DummySigningMetrics {
+		completed: Mutex<Vec<(Option<String>, bool)>>,
+		slow_key_generations: AtomicUsize,
+	}
+
+	impl SigningMetrics for DummySigningMetrics {
+		fn on_session_completed(&self, workload: Option<&str>, is_succeeded: bool, _duration: Duration) {
+			self.completed.lock().push((workload.map(Into::into), is_succeeded));
+		}
+
+		fn on_slow_session_key_generation(&self, _workload: Option<&str>, _duration: Duration) {
+			self.slow_key_generations.fetch_add(1, Ordering::SeqCst);
+		}
+	}
+
+	#[derive(Default)]
+	struct CountingAclStorage {
+		checks: AtomicUsize,
+	}
+
+	impl AclStorage for CountingAclStorage {
+		fn check(&self, _public: &Public, _document: &SessionId) -> Result<bool, ServerError> {
+			self.checks.fetch_add(1, Ordering::SeqCst);
+			Ok(true)
+		}
+	}
+
+	struct SlowAclStorage {
+		delay: Duration,
+	}
+
+	impl AclStorage for SlowAclStorage {
+		fn check(&self, _public: &Public, _document: &SessionId) -> Result<bool, ServerError> {
+			thread::sleep(self.delay);
+			Ok(true)
+		}
+	}
+
+	impl ErrorMapper for AccessDeniedErrorMapper {
+		fn map_error(&self, error: Error) -> Error {
+			match error {
+				Error::AccessDenied => Error::ConsensusUnreachable { retry_after: None },
+				error => error,
+			}
+		}
+	}
 
 	struct Node {
 		pub node_id: NodeId,
@@ -829,16 +2861,31 @@ mod tests {
 
 	impl MessageLoop {
 		pub fn new(gl: &KeyGenerationMessageLoop) -> Self {
+			Self::with_requester(gl, Random.generate().unwrap())
+		}
+
+		pub fn with_requester(gl: &KeyGenerationMessageLoop, requester: KeyPair) -> Self {
+			Self::with_options(gl, requester, |_| Default::default())
+		}
+
+		/// Create sessions with options, returned for every node by its index (master node has index 0).
+		pub fn with_options<F>(gl: &KeyGenerationMessageLoop, requester: KeyPair, options: F) -> Self where F: Fn(usize) -> SigningSessionOptions {
+			let acl_storages: Vec<_> = gl.nodes.keys().map(|_| Arc::new(DummyAclStorage::default())).collect();
+			let mut sl = Self::with_acl_storages(gl, requester, Default::default(), options, |i| acl_storages[i].clone() as Arc<AclStorage>);
+			sl.acl_storages = acl_storages;
+			sl
+		}
+
+		/// Create sessions with options and ACL storages, returned for every node by its index (master node has index 0).
+		/// Request options are only passed to the master node.
+		pub fn with_acl_storages<F, A>(gl: &KeyGenerationMessageLoop, requester: KeyPair, request_options: SigningRequestOptions, options: F, acl_storage: A) -> Self
+			where F: Fn(usize) -> SigningSessionOptions, A: Fn(usize) -> Arc<AclStorage> {
 			let version = gl.nodes.values().nth(0).unwrap().key_storage.get(&Default::default()).unwrap().unwrap().versions.iter().last().unwrap().hash;
 			let mut nodes = BTreeMap::new();
 			let session_id = gl.session_id.clone();
-			let requester = Random.generate().unwrap();
 			let signature = Some(ethkey::sign(requester.secret(), &SessionId::default()).unwrap());
 			let master_node_id = gl.nodes.keys().nth(0).unwrap().clone();
-			let mut acl_storages = Vec::new();
 			for (i, (gl_node_id, gl_node)) in gl.nodes.iter().enumerate() {
-				let acl_storage = Arc::new(DummyAclStorage::default());
-				acl_storages.push(acl_storage.clone());
 				let cluster = Arc::new(DummyCluster::new(gl_node_id.clone()));
 				let session = SessionImpl::new(SessionParams {
 					meta: SessionMeta {
@@ -849,9 +2896,13 @@ mod tests {
 					},
 					access_key: "834cb736f02d9c968dfaf0c37658a1d86ff140554fc8b59c9fdad5a8cf810eec".parse().unwrap(),
 					key_share: Some(gl_node.key_storage.get(&session_id).unwrap().unwrap()),
-					acl_storage: acl_storage,
+					acl_storage: acl_storage(i),
 					cluster: cluster.clone(),
 					nonce: 0,
+					max_lifetime: None,
+					mandatory_nodes: Default::default(),
+					request_options: if i == 0 { request_options.clone() } else { Default::default() },
+					options: options(i),
 				}, if i == 0 { signature.clone() } else { None }).unwrap();
 				nodes.insert(gl_node_id.clone(), Node { node_id: gl_node_id.clone(), cluster: cluster, key_storage: gl_node.key_storage.clone(), session: session });
 			}
@@ -868,7 +2919,7 @@ mod tests {
 				requester: requester,
 				nodes: nodes,
 				queue: VecDeque::new(),
-				acl_storages: acl_storages,
+				acl_storages: Vec::new(),
 				version: version,
 			}
 		}
@@ -923,16 +2974,29 @@ mod tests {
 	}
 
 	fn prepare_signing_sessions(threshold: usize, num_nodes: usize) -> (KeyGenerationMessageLoop, MessageLoop) {
+		prepare_signing_sessions_with_options(threshold, num_nodes, |_| Default::default())
+	}
+
+	fn prepare_signing_sessions_with_options<F>(threshold: usize, num_nodes: usize, options: F) -> (KeyGenerationMessageLoop, MessageLoop)
+		where F: Fn(usize) -> SigningSessionOptions {
 		// run key generation sessions
-		let mut gl = KeyGenerationMessageLoop::new(num_nodes);
+		let gl = run_key_generation(threshold, num_nodes);
+
+		// run signing session
+		let sl = MessageLoop::with_options(&gl, Random.generate().unwrap(), options);
+		(gl, sl)
+	}
+
+	fn run_key_generation(threshold: usize, num_nodes: usize) -> KeyGenerationMessageLoop {
+		complete_key_generation(threshold, KeyGenerationMessageLoop::new(num_nodes))
+	}
+
+	fn complete_key_generation(threshold: usize, mut gl: KeyGenerationMessageLoop) -> KeyGenerationMessageLoop {
 		gl.master().initialize(Public::default(), threshold, gl.nodes.keys().cloned().collect()).unwrap();
 		while let Some((from, to, message)) = gl.take_message() {
 			gl.process_message((from, to, message)).unwrap();
 		}
-
-		// run signing session
-		let sl = MessageLoop::new(&gl);
-		(gl, sl)
+		gl
 	}
 
 	#[test]
@@ -956,119 +3020,1728 @@ mod tests {
 	}
 
 	#[test]
-	fn constructs_in_cluster_of_single_node() {
-		let mut nodes = BTreeMap::new();
-		let self_node_id = Random.generate().unwrap().public().clone();
-		nodes.insert(self_node_id, Random.generate().unwrap().secret().clone());
-		match SessionImpl::new(SessionParams {
-			meta: SessionMeta {
-				id: SessionId::default(),
-				self_node_id: self_node_id.clone(),
-				master_node_id: self_node_id.clone(),
-				threshold: 0,
-			},
-			access_key: Random.generate().unwrap().secret().clone(),
-			key_share: Some(DocumentKeyShare {
-				author: Public::default(),
-				threshold: 0,
-				public: Default::default(),
-				common_point: Some(Random.generate().unwrap().public().clone()),
-				encrypted_point: Some(Random.generate().unwrap().public().clone()),
-				versions: vec![DocumentKeyShareVersion {
-					hash: Default::default(),
-					id_numbers: nodes,
-					secret_share: Random.generate().unwrap().secret().clone(),
-				}],
-			}),
-			acl_storage: Arc::new(DummyAclStorage::default()),
-			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
-			nonce: 0,
-		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())) {
-			Ok(_) => (),
-			_ => panic!("unexpected"),
+	fn complete_gen_sign_session_with_partial_signature_blinding() {
+		let (gl, mut sl) = prepare_signing_sessions_with_options(2, 5, |_| SigningSessionOptions {
+			partial_signature_blinding: true,
+			..Default::default()
+		});
+		let message_hash = H256::from(777);
+		sl.master().initialize(sl.version.clone(), message_hash).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
 		}
-	}
 
-	#[test]
-	fn fails_to_initialize_if_does_not_have_a_share() {
-		let self_node_id = Random.generate().unwrap().public().clone();
-		let session = SessionImpl::new(SessionParams {
-			meta: SessionMeta {
-				id: SessionId::default(),
-				self_node_id: self_node_id.clone(),
-				master_node_id: self_node_id.clone(),
-				threshold: 0,
-			},
-			access_key: Random.generate().unwrap().secret().clone(),
-			key_share: None,
-			acl_storage: Arc::new(DummyAclStorage::default()),
-			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
-			nonce: 0,
-		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())).unwrap();
-		assert_eq!(session.initialize(Default::default(), Default::default()), Err(Error::InvalidMessage));
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &message_hash).unwrap());
 	}
 
 	#[test]
-	fn fails_to_initialize_if_threshold_is_wrong() {
-		let mut nodes = BTreeMap::new();
-		let self_node_id = Random.generate().unwrap().public().clone();
-		nodes.insert(self_node_id.clone(), Random.generate().unwrap().secret().clone());
-		nodes.insert(Random.generate().unwrap().public().clone(), Random.generate().unwrap().secret().clone());
-		let session = SessionImpl::new(SessionParams {
-			meta: SessionMeta {
-				id: SessionId::default(),
-				self_node_id: self_node_id.clone(),
-				master_node_id: self_node_id.clone(),
-				threshold: 2,
-			},
-			access_key: Random.generate().unwrap().secret().clone(),
-			key_share: Some(DocumentKeyShare {
-				author: Public::default(),
-				threshold: 2,
-				public: Default::default(),
-				common_point: Some(Random.generate().unwrap().public().clone()),
-				encrypted_point: Some(Random.generate().unwrap().public().clone()),
-				versions: vec![DocumentKeyShareVersion {
-					hash: Default::default(),
-					id_numbers: nodes,
-					secret_share: Random.generate().unwrap().secret().clone(),
-				}],
-			}),
-			acl_storage: Arc::new(DummyAclStorage::default()),
-			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
-			nonce: 0,
-		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())).unwrap();
-		assert_eq!(session.initialize(Default::default(), Default::default()), Err(Error::ConsensusUnreachable));
+	fn node_health_tracker_is_updated_with_partial_signatures() {
+		let node_health = Arc::new(NodeHealthTracker::default());
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			node_health_tracker: Some(node_health.clone()),
+			..Default::default()
+		});
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		// master has received partial signature from single other node
+		let successes: u64 = sl.nodes.keys().skip(1).map(|node| node_health.health(node).successes).sum();
+		assert_eq!(successes, 1);
 	}
 
 	#[test]
-	fn fails_to_initialize_when_already_initialized() {
-		let (_, sl) = prepare_signing_sessions(1, 3);
-		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Ok(()));
-		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Err(Error::InvalidStateForRequest));
+	fn single_node_session_reports_same_transitions_as_multi_node_session() {
+		let mut transitions = Vec::new();
+		for &(threshold, num_nodes) in &[(0, 1), (1, 3)] {
+			let (_, mut sl) = prepare_signing_sessions(threshold, num_nodes);
+			sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+			while let Some((from, to, message)) = sl.take_message() {
+				sl.process_message((from, to, message)).unwrap();
+			}
+
+			assert!(sl.master().wait().is_ok());
+			transitions.push(sl.master().state_transitions());
+		}
+
+		assert_eq!(transitions[0], vec![SessionState::ConsensusEstablishing, SessionState::SessionKeyGeneration, SessionState::SignatureComputing]);
+		assert_eq!(transitions[0], transitions[1]);
 	}
 
 	#[test]
-	fn does_not_fail_when_consensus_message_received_after_consensus_established() {
+	fn diagnostics_contain_no_secrets() {
 		let (_, mut sl) = prepare_signing_sessions(1, 3);
-		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
-		// consensus is established
-		sl.run_until(|sl| sl.master().state() == SessionState::SessionKeyGeneration).unwrap();
-		// but 3rd node continues to send its messages
-		// this should not fail session
-		let consensus_group = sl.master().data.lock().consensus_session.select_consensus_group().unwrap().clone();
-		let mut had_3rd_message = false;
+		let message_hash = H256::from(777);
+		sl.master().initialize(sl.version.clone(), message_hash).unwrap();
 		while let Some((from, to, message)) = sl.take_message() {
-			if !consensus_group.contains(&from) {
-				had_3rd_message = true;
-				sl.process_message((from, to, message)).unwrap();
-			}
+			sl.process_message((from, to, message)).unwrap();
+		}
+		let signature = sl.master().wait().unwrap();
+
+		let diagnostics = sl.master().diagnostics();
+		assert_eq!(diagnostics.self_node_id, diagnostics.master_node_id);
+		assert!(diagnostics.is_master);
+		assert_eq!(diagnostics.threshold, 1);
+		assert_eq!(diagnostics.state, SessionState::SignatureComputing);
+		assert_eq!(diagnostics.version.map(|v| v.0), Some(sl.version.clone()));
+		assert_eq!(diagnostics.message_hash.map(|h| h.0), Some(message_hash));
+		assert!(diagnostics.is_session_key_generation_started);
+		assert!(diagnostics.is_session_key_generated);
+		assert_eq!(diagnostics.partial_signatures_count, 2);
+		assert!(diagnostics.delegated_to.is_none());
+		assert!(diagnostics.delegated_from.is_none());
+		assert!(diagnostics.error.is_none());
+
+		let serialized = serde_json::to_string(&sl.master().diagnostics()).unwrap();
+		let secret_share = sl.master().core.key_share.as_ref().unwrap().version(&sl.version).unwrap().secret_share.clone();
+		let secrets = vec![sl.master().core.access_key.clone(), secret_share, signature.0, signature.1];
+		for secret in secrets {
+			assert!(!serialized.contains(&secret.to_hex()));
 		}
-		assert!(had_3rd_message);
 	}
 
 	#[test]
-	fn fails_when_consensus_message_is_received_when_not_initialized() {
+	fn session_error_is_mapped_by_error_mapper() {
+		let (_, sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			error_mapper: Some(Arc::new(AccessDeniedErrorMapper)),
+			..Default::default()
+		});
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.master().on_session_error(&master_id, Error::AccessDenied);
+		assert_eq!(sl.master().wait(), Err(Error::ConsensusUnreachable { retry_after: None }));
+	}
+
+	#[test]
+	fn signing_fails_when_all_nodes_belong_to_single_organization() {
+		let gl = run_key_generation(1, 3);
+		let node_org: BTreeMap<_, _> = gl.nodes.keys().map(|node| (node.clone(), H256::from(1))).collect();
+		let mut sl = MessageLoop::with_options(&gl, Random.generate().unwrap(), |_| SigningSessionOptions {
+			organizations_policy: Some(OrganizationsPolicy {
+				node_org: node_org.clone(),
+				min_orgs: 2,
+			}),
+			..Default::default()
+		});
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// consensus is established, but selected group spans single organization
+		assert_eq!(sl.run_until(|_| false), Err(Error::ConsensusUnreachable { retry_after: None }));
+		assert!(sl.master().data.lock().generation_session.is_none());
+	}
+
+	#[test]
+	fn repeated_request_is_served_from_signature_cache() {
+		let cache = Arc::new(SignatureCache::new(Duration::from_secs(60)));
+		let with_cache = |_: usize| SigningSessionOptions { signature_cache: Some(cache.clone()), ..Default::default() };
+		let (gl, mut sl) = prepare_signing_sessions_with_options(1, 3, &with_cache);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		let signature = sl.master().wait().unwrap();
+
+		// the same request is served by the new session without sending any messages
+		let requester = sl.requester.clone();
+		let mut sl = MessageLoop::with_options(&gl, requester, &with_cache);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		assert!(sl.take_message().is_none());
+		assert_eq!(sl.master().wait(), Ok(signature));
+	}
+
+	#[test]
+	fn cached_signature_is_not_served_to_requester_without_access() {
+		let cache = Arc::new(SignatureCache::new(Duration::from_secs(60)));
+		let with_cache = |_: usize| SigningSessionOptions { signature_cache: Some(cache.clone()), ..Default::default() };
+		let (gl, mut sl) = prepare_signing_sessions_with_options(1, 3, &with_cache);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		// other requester doesn't get cached signature
+		let mut other_sl = MessageLoop::with_options(&gl, Random.generate().unwrap(), &with_cache);
+		for acl_storage in &other_sl.acl_storages {
+			acl_storage.prohibit(other_sl.requester.public().clone(), SessionId::default());
+		}
+		other_sl.master().initialize(other_sl.version.clone(), 777.into()).unwrap();
+		assert!(other_sl.master().data.lock().result.is_none());
+		assert!(other_sl.run_until(|_| false).is_err());
+
+		// the same requester, whose access has been revoked, doesn't get cached signature too
+		let requester = sl.requester.clone();
+		let mut sl = MessageLoop::with_options(&gl, requester, &with_cache);
+		for acl_storage in &sl.acl_storages {
+			acl_storage.prohibit(sl.requester.public().clone(), SessionId::default());
+		}
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		assert!(sl.master().data.lock().result.is_none());
+		assert!(sl.run_until(|_| false).is_err());
+	}
+
+	#[test]
+	fn timing_breakdown_is_populated_after_signing() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		let timing = sl.master().timing_breakdown();
+		assert!(timing.compute > Duration::from_secs(0));
+	}
+
+	#[test]
+	fn timing_breakdown_network_time_is_only_accounted_in_explicit_waits() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// processing messages does not account network time
+		assert_eq!(sl.master().timing_breakdown().network, Duration::from_secs(0));
+
+		// waiting for completion does
+		assert_eq!(sl.master().wait_with_timeout(Some(Duration::from_millis(50))), Err(Error::SessionTimeout));
+		assert!(sl.master().timing_breakdown().network >= Duration::from_millis(50));
+		assert_eq!(sl.master().timing_breakdown().compute, Duration::from_secs(0));
+	}
+
+	#[test]
+	fn timing_breakdown_reports_consensus_duration() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+
+		// replace session on one of slave nodes with the session, which is using slow ACL storage
+		let slave_id = sl.nodes.keys().nth(1).cloned().unwrap();
+		let session = {
+			let slave = &sl.nodes[&slave_id];
+			SessionImpl::new(SessionParams {
+				meta: slave.session.core.meta.clone(),
+				access_key: slave.session.core.access_key.clone(),
+				key_share: slave.key_storage.get(&SessionId::default()).unwrap(),
+				acl_storage: Arc::new(SlowAclStorage { delay: Duration::from_millis(100) }),
+				cluster: slave.cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: Default::default(),
+				options: Default::default(),
+			}, None).unwrap()
+		};
+		sl.nodes.get_mut(&slave_id).unwrap().session = session;
+
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		let timing = sl.master().timing_breakdown();
+		assert!(timing.consensus >= Duration::from_millis(100));
+	}
+
+	#[test]
+	fn signature_is_computed_when_completion_notification_fails() {
+		let (gl, mut sl) = prepare_signing_sessions(1, 3);
+		let message_hash = H256::from(777);
+		sl.master().initialize(sl.version.clone(), message_hash).unwrap();
+
+		// completion notifications from master can not be delivered
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		let other_nodes: Vec<_> = sl.nodes.keys().skip(1).cloned().collect();
+		loop {
+			let (from, to, message) = sl.take_message().unwrap();
+			let is_partial_signature = match message {
+				Message::Signing(SigningMessage::PartialSignature(_)) => true,
+				_ => false,
+			};
+			if is_partial_signature {
+				for node in &other_nodes {
+					sl.nodes[&master_id].cluster.make_unreachable(node.clone());
+				}
+			}
+
+			sl.process_message((from, to, message)).unwrap();
+			if is_partial_signature {
+				break;
+			}
+		}
+
+		// => signature is still computed
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &message_hash).unwrap());
+	}
+
+	#[test]
+	fn consensus_unreachable_error_suggests_retry_delay() {
+		let (_, sl) = prepare_signing_sessions(2, 5);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		let other_nodes: Vec<_> = sl.nodes.keys().skip(1).cloned().collect();
+
+		// only 1 of 4 other nodes is connected => 2 nodes are missing to reach consensus of 3 nodes
+		for node in other_nodes.iter().skip(1) {
+			sl.nodes[&master_id].cluster.remove_node(node);
+		}
+
+		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Err(Error::ConsensusUnreachable {
+			retry_after: Some(Duration::from_secs(CONSENSUS_RETRY_BACKOFF * 2)),
+		}));
+	}
+
+	#[test]
+	fn consensus_retry_backoff_can_be_overridden() {
+		let (_, sl) = prepare_signing_sessions_with_options(2, 5, |_| SigningSessionOptions {
+			consensus_retry_backoff: Some(Duration::from_secs(10)),
+			..Default::default()
+		});
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		let other_nodes: Vec<_> = sl.nodes.keys().skip(1).cloned().collect();
+		for node in other_nodes.iter().skip(1) {
+			sl.nodes[&master_id].cluster.remove_node(node);
+		}
+
+		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Err(Error::ConsensusUnreachable {
+			retry_after: Some(Duration::from_secs(20)),
+		}));
+	}
+
+	#[test]
+	fn consensus_remaining_decreases_as_confirmations_arrive() {
+		let (_, mut sl) = prepare_signing_sessions(2, 5);
+		assert_eq!(sl.master().consensus_remaining(), None);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// master has confirmed itself => 2 more confirmations are required
+		let mut remaining = vec![sl.master().consensus_remaining()];
+		while sl.master().state() == SessionState::ConsensusEstablishing {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+
+			let current_remaining = sl.master().consensus_remaining();
+			if remaining.last() != Some(&current_remaining) {
+				remaining.push(current_remaining);
+			}
+		}
+
+		assert_eq!(remaining, vec![Some(2), Some(1), None]);
+	}
+
+	fn der_decode_unsigned_integer(encoded: &[u8]) -> (H256, &[u8]) {
+		assert_eq!(encoded[0], 0x02);
+		let len = encoded[1] as usize;
+		let value = &encoded[2..2 + len];
+		assert!(value.len() == 1 || value[0] != 0 || value[1] & 0x80 != 0);
+		let mut decoded = H256::default();
+		let value = if value.len() == 33 { &value[1..] } else { value };
+		decoded[32 - value.len()..].copy_from_slice(value);
+		(decoded, &encoded[2 + len..])
+	}
+
+	#[test]
+	fn der_encoded_signature_decodes_to_the_same_signature() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let signature = sl.master().wait().unwrap();
+		let encoded = sl.master().wait_der().unwrap();
+		assert_eq!(encoded[0], 0x30);
+		assert_eq!(encoded[1] as usize, encoded.len() - 2);
+		let (c, rest) = der_decode_unsigned_integer(&encoded[2..]);
+		let (s, rest) = der_decode_unsigned_integer(rest);
+		assert!(rest.is_empty());
+		assert_eq!((Secret::from(c), Secret::from(s)), signature);
+	}
+
+	#[test]
+	fn signature_is_converted_into_custom_representation() {
+		struct SwappedSignature(Secret, Secret);
+
+		impl FromSignature for SwappedSignature {
+			fn from_signature(c: &Secret, s: &Secret) -> Result<Self, Error> {
+				Ok(SwappedSignature(s.clone(), c.clone()))
+			}
+		}
+
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let (c, s) = sl.master().wait().unwrap();
+		let swapped: SwappedSignature = sl.master().wait_as().unwrap();
+		assert_eq!((swapped.0, swapped.1), (s.clone(), c.clone()));
+
+		let compact: CompactSignature = sl.master().wait_as().unwrap();
+		assert_eq!(&compact.0[..32], &c[..]);
+		assert_eq!(&compact.0[32..], &s[..]);
+	}
+
+	#[test]
+	fn signature_is_tagged_with_scheme() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let tagged: TaggedSignature = sl.master().wait_as().unwrap();
+		assert_eq!(sl.master().scheme(), SignatureScheme::SchnorrSecp256k1);
+		assert_eq!(tagged.scheme, SignatureScheme::SchnorrSecp256k1);
+		assert_eq!(tagged.signature, sl.master().wait().unwrap());
+	}
+
+	#[test]
+	fn der_encoding_handles_leading_zeros_and_high_bit() {
+		let mut c = [0u8; 32];
+		c[31] = 0x01;
+		let mut s = [0u8; 32];
+		s[0] = 0x80;
+		s[31] = 0x02;
+
+		let mut expected = vec![0x30, 0x26, 0x02, 0x01, 0x01, 0x02, 0x21, 0x00];
+		expected.extend_from_slice(&s);
+		assert_eq!(der_encode_signature(&c, &s), expected);
+		assert_eq!(der_encode_signature(&[0u8; 32], &[0x7f; 32])[..5], [0x30, 0x25, 0x02, 0x01, 0x00]);
+	}
+
+	#[test]
+	fn signing_fails_when_mandatory_node_rejects_consensus() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		let mandatory_node_id = sl.nodes.keys().nth(1).cloned().unwrap();
+		sl.nodes.get_mut(&master_id).unwrap().session.core.mandatory_nodes.insert(mandatory_node_id);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// master and 3rd node are enough to reach consensus, but mandatory node disagrees
+		sl.acl_storages[1].prohibit(sl.requester.public().clone(), SessionId::default());
+
+		// => session fails
+		assert_eq!(sl.run_until(|_| false), Err(Error::AccessDenied));
+		assert_eq!(sl.master().wait(), Err(Error::AccessDenied));
+
+		// => && error is broadcasted to other nodes
+		let mut error_receivers = BTreeSet::new();
+		while let Some((from, to, message)) = sl.take_message() {
+			if let Message::Signing(SigningMessage::SigningSessionError(_)) = message {
+				if from == master_id {
+					error_receivers.insert(to);
+				}
+			}
+		}
+		assert_eq!(error_receivers, sl.nodes.keys().skip(1).cloned().collect());
+	}
+
+	#[test]
+	fn complete_gen_sign_session_with_batched_requests() {
+		let (gl, mut sl) = prepare_signing_sessions_with_options(3, 8, |_| SigningSessionOptions {
+			requests_batch_size: Some(2),
+			..Default::default()
+		});
+		let message_hash = H256::from(777);
+		sl.master().initialize(sl.version.clone(), message_hash).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		// all nodes have received consensus request
+		for node in sl.nodes.values().skip(1) {
+			assert!(node.session.data.lock().consensus_session.state() != ConsensusSessionState::WaitingForInitialization);
+		}
+
+		// signature is computed
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &message_hash).unwrap());
+	}
+
+	#[test]
+	fn consensus_detail_reflects_nodes_responses() {
+		let (_, mut sl) = prepare_signing_sessions(2, 5);
+		let nodes_ids: Vec<_> = sl.nodes.keys().cloned().collect();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		sl.acl_storages[1].prohibit(sl.requester.public().clone(), SessionId::default());
+
+		// step until master receives first response
+		while sl.master().consensus_detail().rejected.is_empty() {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		let detail = sl.master().consensus_detail();
+		assert_eq!(detail.confirmed, vec![nodes_ids[0].clone()].into_iter().collect());
+		assert_eq!(detail.rejected, vec![nodes_ids[1].clone()].into_iter().collect());
+		assert_eq!(detail.pending, nodes_ids[2..].iter().cloned().collect());
+	}
+
+	#[test]
+	fn partial_arrival_order_matches_delivery_order() {
+		let (_, mut sl) = prepare_signing_sessions(2, 5);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		let mut delivery_order = Vec::new();
+		while let Some((from, to, message)) = sl.take_message() {
+			if let Message::Signing(SigningMessage::PartialSignature(_)) = message {
+				if to == master_id {
+					delivery_order.push(from.clone());
+				}
+			}
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		let arrival_order: Vec<_> = sl.master().partial_arrival_order().into_iter().map(|(node, _)| node).collect();
+		assert_eq!(delivery_order.len(), 2);
+		assert_eq!(arrival_order, delivery_order);
+	}
+
+	#[test]
+	fn fails_to_start_session_key_generation_if_version_is_unknown() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		sl.master().data.lock().version = None;
+
+		assert_eq!(sl.run_until(|_| false), Err(Error::InvalidStateForRequest));
+		assert!(sl.master().data.lock().generation_session.is_none());
+	}
+
+	#[test]
+	fn master_attests_signing_nodes() {
+		let master_key_pair = Random.generate().unwrap();
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			attestation_key_pair: Some(Arc::new(PlainNodeKeyPair::new(master_key_pair.clone()))),
+			..Default::default()
+		});
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		let message_hash = H256::from(777);
+		sl.master().initialize(sl.version.clone(), message_hash).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		let attestation = sl.master().attestation().unwrap();
+		assert_eq!(attestation.signers.len(), 2);
+		assert!(attestation.signers.contains(&master_id));
+		let hash = attestation_hash(&sl.session_id, &message_hash, &sl.version, &attestation.signers);
+		assert!(ethkey::verify_public(master_key_pair.public(), &attestation.signature, &hash).unwrap());
+	}
+
+	#[test]
+	fn signing_completes_over_flaky_transport_when_retried() {
+		let (gl, _) = prepare_signing_sessions(1, 3);
+		let message_hash = H256::from(777);
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		for seed in 0..10u64 {
+			// client retries failed session until succeeded
+			let mut signature = None;
+			for attempt in 0..20u64 {
+				let mut sl = MessageLoop::new(&gl);
+				for (i, node) in sl.nodes.values().enumerate() {
+					node.cluster.set_send_failure_probability(0.02, seed * 1000 + attempt * 10 + i as u64);
+				}
+
+				if sl.master().initialize(sl.version.clone(), message_hash).is_err() {
+					continue;
+				}
+				while let Some((from, to, message)) = sl.take_message() {
+					if sl.process_message((from, to, message)).is_err() {
+						break;
+					}
+				}
+
+				// if failed send has been ignored, session would be stalled => it is only finished by timeout
+				if let Some(Ok(result)) = sl.master().data.lock().result.clone() {
+					signature = Some(result);
+					break;
+				}
+			}
+
+			assert!(math::verify_signature(&public, &signature.unwrap(), &message_hash).unwrap());
+		}
+	}
+
+	#[test]
+	fn consensus_unreachable_error_has_remediation_hint() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		sl.acl_storages[1].prohibit(sl.requester.public().clone(), SessionId::default());
+		sl.acl_storages[2].prohibit(sl.requester.public().clone(), SessionId::default());
+
+		let error = sl.run_until(|_| false).unwrap_err();
+		sl.master().on_session_error(&master_id, error);
+
+		let error = sl.master().wait_detailed().unwrap_err();
+		assert_eq!(error.error, Error::ConsensusUnreachable { retry_after: None });
+		assert_eq!(error.phase, SessionState::ConsensusEstablishing);
+		assert_eq!(error.node, None);
+		assert_eq!(error.remediation, "too few key servers have agreed to sign; ensure at least 2 key servers are reachable and allow access to the key");
+	}
+
+	#[test]
+	fn signs_same_message_hash_with_multiple_key_versions() {
+		let (gl, _) = prepare_signing_sessions(1, 3);
+
+		// add new key version to every node by resharing the same joint secret
+		let (joint_public, joint_secret) = gl.master().joint_public_and_secret().unwrap().unwrap();
+		let mut polynom = math::generate_random_polynom(1).unwrap();
+		polynom[0] = joint_secret;
+		let id_numbers: BTreeMap<_, _> = gl.nodes.keys().map(|n| (n.clone(), math::generate_random_scalar().unwrap())).collect();
+		for (node_id, node) in &gl.nodes {
+			let mut key_share = node.key_storage.get(&SessionId::default()).unwrap().unwrap();
+			let secret_share = math::compute_polynom(&polynom, &id_numbers[node_id]).unwrap();
+			key_share.versions.push(DocumentKeyShareVersion::new(id_numbers.clone(), secret_share));
+			node.key_storage.update(SessionId::default(), key_share).unwrap();
+		}
+
+		// run signing sessions for both versions in parallel
+		let message_hash = H256::from(777);
+		let mut sl1 = MessageLoop::new(&gl);
+		let mut sl2 = MessageLoop::new(&gl);
+		let versions: Vec<_> = gl.nodes.values().nth(0).unwrap().key_storage.get(&SessionId::default()).unwrap().unwrap()
+			.versions.iter().map(|v| v.hash.clone()).collect();
+		assert_eq!(versions.len(), 2);
+		sl1.master().initialize(versions[0].clone(), message_hash).unwrap();
+		sl2.master().initialize(versions[1].clone(), message_hash).unwrap();
+		loop {
+			let message1 = sl1.take_message();
+			let message2 = sl2.take_message();
+			if message1.is_none() && message2.is_none() {
+				break;
+			}
+			if let Some(message1) = message1 {
+				sl1.process_message(message1).unwrap();
+			}
+			if let Some(message2) = message2 {
+				sl2.process_message(message2).unwrap();
+			}
+		}
+
+		let signatures = SessionImpl::wait_all_versions(vec![sl1.master(), sl2.master()]).unwrap();
+		assert_eq!(signatures.keys().cloned().collect::<Vec<_>>(), {
+			let mut sorted_versions = versions.clone();
+			sorted_versions.sort();
+			sorted_versions
+		});
+		for version in &versions {
+			assert!(math::verify_signature(&joint_public, &signatures[version], &message_hash).unwrap());
+		}
+	}
+
+	#[test]
+	fn missing_session_key_is_reported_as_invalid_state() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		loop {
+			let (from, to, message) = sl.take_message().unwrap();
+			if let Message::Signing(SigningMessage::RequestPartialSignature(_)) = message {
+				sl.nodes[&to].session.data.lock().generation_session = None;
+				assert_eq!(sl.nodes[&to].session.on_message(&from, &message), Err(Error::InvalidStateForRequest));
+				break;
+			}
+
+			sl.process_message((from, to, message)).unwrap();
+		}
+	}
+
+	#[test]
+	fn canonical_message_hash_does_not_depend_on_fields_order() {
+		let message1 = vec![("to".to_owned(), vec![1, 2]), ("amount".to_owned(), vec![3])];
+		let message2 = vec![("amount".to_owned(), vec![3]), ("to".to_owned(), vec![1, 2])];
+		assert_eq!(canonical_message_hash(&message1).unwrap(), canonical_message_hash(&message2).unwrap());
+
+		let message3 = vec![("amount".to_owned(), vec![3, 1]), ("to".to_owned(), vec![2])];
+		assert!(canonical_message_hash(&message1).unwrap() != canonical_message_hash(&message3).unwrap());
+
+		let message4 = vec![("to".to_owned(), vec![1]), ("to".to_owned(), vec![2])];
+		assert_eq!(canonical_message_hash(&message4), Err(Error::InvalidMessage));
+	}
+
+	#[test]
+	fn signs_canonical_message_hash_when_required() {
+		let (gl, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			require_canonical_message: true,
+			..Default::default()
+		});
+		let message = vec![("to".to_owned(), vec![1, 2]), ("amount".to_owned(), vec![3])];
+		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Err(Error::InvalidMessage));
+		sl.master().initialize_structured(sl.version.clone(), &message).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &canonical_message_hash(&message).unwrap()).unwrap());
+	}
+
+	#[test]
+	fn signs_merkle_root_of_leaves() {
+		let (gl, mut sl) = prepare_signing_sessions(1, 3);
+		let leaves: Vec<Vec<u8>> = (0u8..5).map(|i| vec![i; i as usize + 1]).collect();
+		let root = sl.master().initialize_merkle(sl.version.clone(), &leaves).unwrap();
+		assert_eq!(root, merkle_root(&leaves).unwrap());
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &root).unwrap());
+
+		// every leaf (including the promoted last one) is included in the signed root
+		for (index, leaf) in leaves.iter().enumerate() {
+			let proof = merkle_proof(&leaves, index).unwrap();
+			assert!(verify_merkle_proof(&root, leaf, &proof));
+		}
+
+		// proof of one leaf doesn't work for another leaf
+		let proof = merkle_proof(&leaves, 1).unwrap();
+		assert!(!verify_merkle_proof(&root, &leaves[2], &proof));
+		assert_eq!(merkle_proof(&leaves, 5), Err(Error::InvalidMessage));
+		assert_eq!(merkle_root(&[]), Err(Error::InvalidMessage));
+	}
+
+	#[test]
+	fn signing_throughput_benchmark_works() {
+		let throughput = benchmark::benchmark_signing_throughput(Duration::from_millis(10)).unwrap();
+		assert!(throughput > 0f64);
+	}
+
+	#[test]
+	fn access_decisions_are_cached_for_ttl() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let acl_storage = Arc::new(CountingAclStorage::default());
+		let options = SigningSessionOptions {
+			access_decisions_cache: Some(Arc::new(AccessDecisionsCache::new(Duration::from_millis(300)))),
+			..Default::default()
+		};
+		let node = sl.nodes.values().nth(0).unwrap();
+		let sign = || {
+			let session = SessionImpl::new(SessionParams {
+				meta: node.session.core.meta.clone(),
+				access_key: Random.generate().unwrap().secret().clone(),
+				key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+				acl_storage: acl_storage.clone(),
+				cluster: node.cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: Default::default(),
+				options: options.clone(),
+			}, Some(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap())).unwrap();
+			session.initialize(sl.version.clone(), 777.into()).unwrap();
+			session.wait().unwrap();
+		};
+
+		// second request within TTL reuses cached decision
+		sign();
+		sign();
+		assert_eq!(acl_storage.checks.load(Ordering::SeqCst), 1);
+
+		// request after TTL queries ACL storage again
+		thread::sleep(Duration::from_millis(400));
+		sign();
+		assert_eq!(acl_storage.checks.load(Ordering::SeqCst), 2);
+	}
+
+	#[test]
+	fn access_decisions_are_cached_on_slave_nodes() {
+		let gl = run_key_generation(1, 3);
+		let requester = Random.generate().unwrap();
+		let acl_storages: Vec<_> = (0..3).map(|_| Arc::new(CountingAclStorage::default())).collect();
+		let decisions_caches: Vec<_> = (0..3).map(|_| Arc::new(AccessDecisionsCache::new(Duration::from_secs(60)))).collect();
+		for _ in 0..2 {
+			let mut sl = MessageLoop::with_acl_storages(&gl, requester.clone(), Default::default(), |i| SigningSessionOptions {
+				access_decisions_cache: Some(decisions_caches[i].clone()),
+				..Default::default()
+			}, |i| acl_storages[i].clone() as Arc<AclStorage>);
+			sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+			while let Some((from, to, message)) = sl.take_message() {
+				sl.process_message((from, to, message)).unwrap();
+			}
+			assert!(sl.master().wait().is_ok());
+		}
+
+		// slaves have only checked ACL when serving the first request
+		for acl_storage in acl_storages.iter().skip(1) {
+			assert_eq!(acl_storage.checks.load(Ordering::SeqCst), 1);
+		}
+	}
+
+	#[test]
+	fn metrics_and_diagnostics_carry_workload() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let metrics = Arc::new(DummySigningMetrics::default());
+		let options = SigningSessionOptions {
+			metrics: Some(metrics.clone()),
+			..Default::default()
+		};
+		let node = sl.nodes.values().nth(0).unwrap();
+		let sessions: Vec<_> = vec![Some("bridge"), Some("custody"), Some("bridge"), None].into_iter().map(|workload| {
+			let session = SessionImpl::new(SessionParams {
+				meta: node.session.core.meta.clone(),
+				access_key: Random.generate().unwrap().secret().clone(),
+				key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+				acl_storage: Arc::new(DummyAclStorage::default()),
+				cluster: node.cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: SigningRequestOptions {
+					workload: workload.map(Into::into),
+					..Default::default()
+				},
+				options: options.clone(),
+			}, Some(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap())).unwrap();
+			session.initialize(sl.version.clone(), 777.into()).unwrap();
+			session
+		}).collect();
+
+		assert_eq!(*metrics.completed.lock(), vec![
+			(Some("bridge".into()), true),
+			(Some("custody".into()), true),
+			(Some("bridge".into()), true),
+			(None, true),
+		]);
+		assert_eq!(sessions[1].diagnostics().workload, Some("custody".into()));
+		assert_eq!(sessions.iter().filter(|s| s.workload() == Some("bridge")).count(), 2);
+	}
+
+	#[test]
+	fn computation_limiter_bounds_concurrent_computations() {
+		let limiter = Arc::new(ComputationLimiter::new(2));
+		let active = Arc::new(AtomicUsize::new(0));
+		let max_active = Arc::new(Mutex::new(0));
+		let threads: Vec<_> = (0..8).map(|_| {
+			let (limiter, active, max_active) = (limiter.clone(), active.clone(), max_active.clone());
+			thread::spawn(move || for _ in 0..4 {
+				let _permit = limiter.acquire();
+				let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+				{
+					let mut max_active = max_active.lock();
+					*max_active = ::std::cmp::max(*max_active, now_active);
+				}
+				thread::sleep(Duration::from_millis(2));
+				active.fetch_sub(1, Ordering::SeqCst);
+			})
+		}).collect();
+		for thread in threads {
+			thread.join().unwrap();
+		}
+
+		assert!(*max_active.lock() <= 2);
+	}
+
+	#[test]
+	fn signing_session_completes_with_key_generation_limiter() {
+		let limiter = Arc::new(ComputationLimiter::new(1));
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			key_generation_limiter: Some(limiter.clone()),
+			..Default::default()
+		});
+
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+	}
+
+	#[test]
+	fn per_key_computation_limiter_serializes_computations_for_the_same_key() {
+		let limiter = Arc::new(PerKeyComputationLimiter::new(1));
+		let hot_key: SessionId = 1.into();
+		let active = Arc::new(AtomicUsize::new(0));
+		let max_active = Arc::new(Mutex::new(0));
+		let threads: Vec<_> = (0..4).map(|_| {
+			let (limiter, hot_key, active, max_active) = (limiter.clone(), hot_key.clone(), active.clone(), max_active.clone());
+			thread::spawn(move || for _ in 0..4 {
+				let _permit = limiter.acquire(&hot_key);
+				let now_active = active.fetch_add(1, Ordering::SeqCst) + 1;
+				{
+					let mut max_active = max_active.lock();
+					*max_active = ::std::cmp::max(*max_active, now_active);
+				}
+				thread::sleep(Duration::from_millis(2));
+				active.fetch_sub(1, Ordering::SeqCst);
+			})
+		}).collect();
+		for thread in threads {
+			thread.join().unwrap();
+		}
+		assert_eq!(*max_active.lock(), 1);
+
+		// computations for different keys are proceeding concurrently: this would deadlock otherwise
+		let barrier = Arc::new(Barrier::new(2));
+		let threads: Vec<_> = (2u64..4).map(|key_id| {
+			let (limiter, barrier, key_id) = (limiter.clone(), barrier.clone(), SessionId::from(key_id));
+			thread::spawn(move || {
+				let _permit = limiter.acquire(&key_id);
+				barrier.wait();
+			})
+		}).collect();
+		for thread in threads {
+			thread.join().unwrap();
+		}
+	}
+
+	#[test]
+	fn signing_session_completes_with_per_key_generation_limiter() {
+		let limiter = Arc::new(PerKeyComputationLimiter::new(1));
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			key_generation_per_key_limiter: Some(limiter.clone()),
+			..Default::default()
+		});
+
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+	}
+
+	#[test]
+	fn maintenance_mode_rejects_new_sessions_only() {
+		let maintenance_mode = Arc::new(MaintenanceMode::default());
+		let options = SigningSessionOptions {
+			maintenance_mode: Some(maintenance_mode.clone()),
+			..Default::default()
+		};
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| options.clone());
+
+		// session is initialized before node enters maintenance mode
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		maintenance_mode.set_enabled(true);
+
+		// => new session is rejected
+		let master_meta = sl.master().core.meta.clone();
+		let master_key_share = sl.nodes[&master_meta.self_node_id].key_storage.get(&SessionId::default()).unwrap();
+		let master_cluster = sl.nodes[&master_meta.self_node_id].cluster.clone();
+		let requester_signature = ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap();
+		let version = sl.version.clone();
+		let create_session = || {
+			SessionImpl::new(SessionParams {
+				meta: master_meta.clone(),
+				access_key: Random.generate().unwrap().secret().clone(),
+				key_share: master_key_share.clone(),
+				acl_storage: Arc::new(DummyAclStorage::default()),
+				cluster: master_cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: Default::default(),
+				options: options.clone(),
+			}, Some(requester_signature.clone())).unwrap()
+		};
+		assert_eq!(create_session().initialize(version.clone(), 777.into()), Err(Error::NodeInMaintenance));
+
+		// => session, started before maintenance, completes
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+
+		// => new sessions are accepted again when maintenance is over
+		maintenance_mode.set_enabled(false);
+		assert_eq!(create_session().initialize(version.clone(), 777.into()), Ok(()));
+	}
+
+	#[test]
+	fn initialize_rejects_zero_message_hash() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		assert_eq!(sl.master().initialize(sl.version.clone(), H256::zero()), Err(Error::InvalidMessage));
+		assert!(sl.take_message().is_none());
+		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Ok(()));
+	}
+
+	#[test]
+	fn delegated_initialize_rejects_zero_message_hash() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let delegating_node = sl.nodes.keys().nth(1).cloned().unwrap();
+		assert_eq!(sl.master().on_session_delegated(&delegating_node, &SigningSessionDelegation {
+			session: SessionId::default().into(),
+			sub_session: sl.master().core.access_key.clone().into(),
+			session_nonce: 0,
+			requestor_signature: ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap().into(),
+			version: sl.version.clone().into(),
+			message_hash: H256::zero().into(),
+		}), Err(Error::InvalidMessage));
+		assert!(sl.take_message().is_none());
+		assert!(sl.master().data.lock().delegation_status.is_none());
+	}
+
+	fn create_session_with_params(sl: &MessageLoop, meta: SessionMeta, access_key: Secret) -> Result<SessionImpl, Error> {
+		let node = sl.nodes.values().nth(0).unwrap();
+		SessionImpl::new(SessionParams {
+			meta: meta,
+			access_key: access_key,
+			key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+			acl_storage: Arc::new(DummyAclStorage::default()),
+			cluster: node.cluster.clone(),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, None)
+	}
+
+	#[test]
+	fn session_is_created_with_consistent_params() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		let meta = sl.nodes.values().nth(0).unwrap().session.core.meta.clone();
+		assert!(create_session_with_params(&sl, meta, Random.generate().unwrap().secret().clone()).is_ok());
+	}
+
+	#[test]
+	fn session_is_not_created_when_threshold_does_not_match_key_share() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		let mut meta = sl.nodes.values().nth(0).unwrap().session.core.meta.clone();
+		meta.threshold = 2;
+		assert_eq!(create_session_with_params(&sl, meta, Random.generate().unwrap().secret().clone()).err(),
+			Some(Error::InconsistentSessionParams));
+	}
+
+	#[test]
+	fn session_is_not_created_when_node_is_not_key_share_owner() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		let mut meta = sl.nodes.values().nth(0).unwrap().session.core.meta.clone();
+		meta.self_node_id = Random.generate().unwrap().public().clone();
+		assert_eq!(create_session_with_params(&sl, meta, Random.generate().unwrap().secret().clone()).err(),
+			Some(Error::InconsistentSessionParams));
+	}
+
+	#[test]
+	fn session_is_not_created_with_zero_access_key() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		let meta = sl.nodes.values().nth(0).unwrap().session.core.meta.clone();
+		assert_eq!(create_session_with_params(&sl, meta, Secret::from_slice(&[0u8; 32])).err(),
+			Some(Error::InconsistentSessionParams));
+	}
+
+	#[test]
+	fn initialize_fails_with_clear_error_when_key_has_no_versions() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let node = sl.nodes.values().nth(0).unwrap();
+		let mut key_share = node.key_storage.get(&SessionId::default()).unwrap().unwrap();
+		key_share.versions.clear();
+
+		let session = SessionImpl::new(SessionParams {
+			meta: node.session.core.meta.clone(),
+			access_key: Random.generate().unwrap().secret().clone(),
+			key_share: Some(key_share),
+			acl_storage: Arc::new(DummyAclStorage::default()),
+			cluster: node.cluster.clone(),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap())).unwrap();
+		assert_eq!(session.initialize(sl.version.clone(), 777.into()),
+			Err(Error::KeyStorage("key has no versions; run key generation first".into())));
+	}
+
+	struct StaticIdentityVerifier(BTreeMap<String, Address>);
+
+	impl RequesterIdentityVerifier for StaticIdentityVerifier {
+		fn verify(&self, transport_identity: &str, requester: &Address) -> bool {
+			self.0.get(transport_identity) == Some(requester)
+		}
+	}
+
+	#[test]
+	fn session_is_denied_when_requester_does_not_match_transport_identity() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let node = sl.nodes.values().nth(0).unwrap();
+		let other_requester = Random.generate().unwrap();
+		let options = SigningSessionOptions {
+			requester_identity_verifier: Some(Arc::new(StaticIdentityVerifier(vec![
+				("client-1".to_owned(), ethkey::public_to_address(sl.requester.public())),
+				("client-2".to_owned(), ethkey::public_to_address(other_requester.public())),
+			].into_iter().collect()))),
+			..Default::default()
+		};
+		let create_session = |transport_identity: &str| {
+			SessionImpl::new(SessionParams {
+				meta: node.session.core.meta.clone(),
+				access_key: Random.generate().unwrap().secret().clone(),
+				key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+				acl_storage: Arc::new(DummyAclStorage::default()),
+				cluster: node.cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: SigningRequestOptions {
+					transport_identity: Some(transport_identity.to_owned()),
+					..Default::default()
+				},
+				options: options.clone(),
+			}, Some(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap())).unwrap()
+		};
+
+		// request is signed by client-1, but transport has authenticated client-2
+		assert_eq!(create_session("client-2").initialize(sl.version.clone(), 777.into()), Err(Error::AccessDenied));
+
+		// identities match
+		let session = create_session("client-1");
+		session.initialize(sl.version.clone(), 777.into()).unwrap();
+		assert!(session.wait().is_ok());
+	}
+
+	#[test]
+	fn session_data_is_not_locked_while_waiting_for_key_generation_limiter() {
+		let limiter = Arc::new(ComputationLimiter::new(1));
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			key_generation_limiter: Some(limiter.clone()),
+			..Default::default()
+		});
+
+		// run until first session key generation message is sent
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		let (from, to, message) = loop {
+			let (from, to, message) = sl.take_message().unwrap();
+			match message {
+				Message::Signing(SigningMessage::SigningGenerationMessage(_)) => break (from, to, message),
+				_ => sl.process_message((from, to, message)).unwrap(),
+			}
+		};
+
+		// occupy the only computation slot => processing of generation message waits for it
+		let permit = limiter.acquire();
+		let sl = Arc::new(sl);
+		let handle = {
+			let (sl, to) = (sl.clone(), to.clone());
+			thread::spawn(move || sl.nodes[&to].session.on_message(&from, &message))
+		};
+		thread::sleep(Duration::from_millis(100));
+
+		// session data is not locked while waiting => other messages of this session could be processed
+		assert!(sl.nodes[&to].session.data.try_lock().is_some());
+		drop(permit);
+		handle.join().unwrap().unwrap();
+	}
+
+	#[test]
+	fn session_data_is_not_locked_while_waiting_for_per_key_generation_limiter() {
+		let limiter = Arc::new(PerKeyComputationLimiter::new(1));
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			key_generation_per_key_limiter: Some(limiter.clone()),
+			..Default::default()
+		});
+
+		// run until first session key generation message is sent
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		let (from, to, message) = loop {
+			let (from, to, message) = sl.take_message().unwrap();
+			match message {
+				Message::Signing(SigningMessage::SigningGenerationMessage(_)) => break (from, to, message),
+				_ => sl.process_message((from, to, message)).unwrap(),
+			}
+		};
+
+		// occupy the only computation slot for this key (e.g. by concurrent session) => processing waits for it
+		let permit = limiter.acquire(&sl.session_id);
+		let sl = Arc::new(sl);
+		let handle = {
+			let (sl, to) = (sl.clone(), to.clone());
+			thread::spawn(move || sl.nodes[&to].session.on_message(&from, &message))
+		};
+		thread::sleep(Duration::from_millis(100));
+
+		// session data is not locked while waiting => other messages of this session could be processed
+		assert!(sl.nodes[&to].session.data.try_lock().is_some());
+		drop(permit);
+		handle.join().unwrap().unwrap();
+	}
+
+	#[test]
+	fn swapped_acl_storage_is_only_used_by_new_sessions() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let node = sl.nodes.values().nth(0).unwrap();
+		let acl_storage = AclStorageHandle::new(Arc::new(DummyAclStorage::default()));
+		let create_session = || SessionImpl::new(SessionParams {
+			meta: node.session.core.meta.clone(),
+			access_key: Random.generate().unwrap().secret().clone(),
+			key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+			acl_storage: acl_storage.current(),
+			cluster: node.cluster.clone(),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap())).unwrap();
+
+		// session is created before the swap
+		let old_session = create_session();
+
+		// new backend prohibits access to the key
+		let new_backend = DummyAclStorage::default();
+		new_backend.prohibit(sl.requester.public().clone(), SessionId::default());
+		acl_storage.swap(Arc::new(new_backend));
+		let new_session = create_session();
+
+		// old session still uses old backend, new session uses new backend
+		old_session.initialize(sl.version.clone(), 777.into()).unwrap();
+		assert!(old_session.wait().is_ok());
+		assert!(new_session.initialize(sl.version.clone(), 777.into()).is_err());
+	}
+
+	#[test]
+	fn slow_session_key_generation_is_reported() {
+		let metrics = Arc::new(DummySigningMetrics::default());
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |i| SigningSessionOptions {
+			metrics: Some(metrics.clone()),
+			key_generation_soft_threshold: if i == 0 { Some(Duration::from_millis(10)) } else { None },
+			..Default::default()
+		});
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// slow down session key generation
+		thread::sleep(Duration::from_millis(50));
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		// warning is reported, but session is completed
+		assert_eq!(metrics.slow_key_generations.load(Ordering::SeqCst), 1);
+		assert!(sl.master().wait().is_ok());
+	}
+
+	#[test]
+	fn prometheus_metrics_are_rendered() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let metrics = Arc::new(PrometheusSessionMetrics::default());
+		let options = SigningSessionOptions {
+			metrics: Some(metrics.clone()),
+			..Default::default()
+		};
+		let node = sl.nodes.values().nth(0).unwrap();
+		let create_session = || {
+			let session = SessionImpl::new(SessionParams {
+				meta: node.session.core.meta.clone(),
+				access_key: Random.generate().unwrap().secret().clone(),
+				key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+				acl_storage: Arc::new(DummyAclStorage::default()),
+				cluster: node.cluster.clone(),
+				nonce: 0,
+				max_lifetime: None,
+				mandatory_nodes: Default::default(),
+				request_options: Default::default(),
+				options: options.clone(),
+			}, Some(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap())).unwrap();
+			session
+		};
+
+		// 3 successful sessions && 1 failed session
+		for _ in 0..3 {
+			let session = create_session();
+			session.initialize(sl.version.clone(), 777.into()).unwrap();
+			session.wait().unwrap();
+		}
+		create_session().on_session_error(&node.node_id, Error::NodeDisconnected);
+
+		let rendered = metrics.render();
+		assert!(rendered.contains("# TYPE secretstore_signing_sessions_started_total counter\nsecretstore_signing_sessions_started_total 3\n"));
+		assert!(rendered.contains("\nsecretstore_signing_sessions_completed_total 3\n"));
+		assert!(rendered.contains("\nsecretstore_signing_sessions_failed_total 1\n"));
+		assert!(rendered.contains("# TYPE secretstore_signing_duration_seconds histogram\n"));
+		assert!(rendered.contains("\nsecretstore_signing_duration_seconds_bucket{le=\"+Inf\"} 4\n"));
+		assert!(rendered.contains("\nsecretstore_signing_duration_seconds_count 4\n"));
+	}
+
+	#[test]
+	fn second_initialize_call_is_rejected() {
+		let (gl, mut sl) = prepare_signing_sessions(1, 3);
+		let message_hash = H256::from(777);
+		sl.master().initialize(sl.version.clone(), message_hash).unwrap();
+		assert_eq!(sl.master().initialize(sl.version.clone(), 888.into()), Err(Error::InvalidStateForRequest));
+
+		// first initialization is not disturbed
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &message_hash).unwrap());
+	}
+
+	#[test]
+	fn consensus_nodes_decline_session_with_passed_deadline() {
+		let gl = run_key_generation(1, 3);
+		let request_options = SigningRequestOptions {
+			deadline: Some(SystemTime::now() + Duration::from_millis(50)),
+			..Default::default()
+		};
+		let mut sl = MessageLoop::with_acl_storages(&gl, Random.generate().unwrap(), request_options, |_| Default::default(),
+			|_| Arc::new(DummyAclStorage::default()) as Arc<AclStorage>);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		thread::sleep(Duration::from_millis(100));
+
+		// all other nodes are declining participation && master fails
+		let error = loop {
+			let (from, to, message) = sl.take_message().unwrap();
+			if let Message::Signing(SigningMessage::SigningConsensusMessage(ref message)) = message {
+				if let ConsensusMessage::ConfirmConsensusInitialization(ref message) = message.message {
+					assert!(!message.is_confirmed);
+				}
+			}
+
+			if let Err(error) = sl.process_message((from, to, message)) {
+				break error;
+			}
+		};
+
+		assert_eq!(error, Error::SessionTimeout);
+		assert_eq!(sl.master().wait(), Err(Error::SessionTimeout));
+	}
+
+	#[derive(Clone, Default)]
+	struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+	impl Write for SharedBuffer {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn session_outcome_events_are_written() {
+		let buffer = SharedBuffer::default();
+		let event_writer: Box<Write + Send> = Box::new(buffer.clone());
+		let event_writer = Arc::new(Mutex::new(event_writer));
+		let with_event_writer = |i: usize| SigningSessionOptions {
+			event_writer: if i == 0 { Some(event_writer.clone()) } else { None },
+			..Default::default()
+		};
+
+		// successful session
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, &with_event_writer);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		let (c, s) = sl.master().wait().unwrap();
+
+		// session, failed before session key has been generated
+		let (_, mut failed_sl) = prepare_signing_sessions_with_options(1, 3, &with_event_writer);
+		let failed_master_id = failed_sl.nodes.keys().nth(0).cloned().unwrap();
+		failed_sl.master().initialize(failed_sl.version.clone(), 777.into()).unwrap();
+		failed_sl.acl_storages[1].prohibit(failed_sl.requester.public().clone(), SessionId::default());
+		failed_sl.acl_storages[2].prohibit(failed_sl.requester.public().clone(), SessionId::default());
+		let error = failed_sl.run_until(|_| false).unwrap_err();
+		failed_sl.master().on_session_error(&failed_master_id, error);
+
+		let events = String::from_utf8(buffer.0.lock().clone()).unwrap();
+		let events: Vec<serde_json::Value> = events.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+		assert_eq!(events.len(), 2);
+		assert_eq!(events[0]["outcome"], "succeeded");
+		assert_eq!(events[0]["signers"].as_array().unwrap().len(), 2);
+		assert!(events[0]["signers"].as_array().unwrap().contains(&serde_json::to_value(SerializablePublic::from(master_id)).unwrap()));
+		assert!(events[0]["error"].is_null());
+		assert!(events[0]["duration_ms"].is_u64());
+		assert_eq!(events[1]["outcome"], "failed");
+		assert!(events[1]["signers"].as_array().unwrap().is_empty());
+		let expected_error: String = Error::ConsensusUnreachable { retry_after: None }.into();
+		assert_eq!(events[1]["error"], expected_error.as_str());
+
+		// events contain no secrets
+		let serialized = String::from_utf8(buffer.0.lock().clone()).unwrap();
+		for secret in &[sl.master().core.access_key.to_hex(), failed_sl.master().core.access_key.to_hex(), c.to_hex(), s.to_hex()] {
+			assert!(!serialized.contains(secret.as_str()));
+		}
+	}
+
+	#[test]
+	fn failure_report_contains_failure_phase_and_transitions() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		assert!(sl.master().failure_report().is_none());
+
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		sl.acl_storages[1].prohibit(sl.requester.public().clone(), SessionId::default());
+		sl.acl_storages[2].prohibit(sl.requester.public().clone(), SessionId::default());
+		let error = sl.run_until(|_| false).unwrap_err();
+		sl.master().on_session_error(&master_id, error);
+
+		let report = sl.master().failure_report().unwrap();
+		let expected_error: String = Error::ConsensusUnreachable { retry_after: None }.into();
+		assert_eq!(report.error, expected_error);
+		assert_eq!(report.phase, SessionState::ConsensusEstablishing);
+		assert_eq!(report.transitions, vec![SessionState::ConsensusEstablishing]);
+		assert_eq!(report.diagnostics.state, SessionState::ConsensusEstablishing);
+
+		// report contains no secrets
+		let serialized = serde_json::to_string(&report).unwrap();
+		let access_key: String = sl.master().core.access_key.to_hex();
+		assert!(!serialized.contains(&access_key));
+	}
+
+	#[test]
+	fn constructs_in_cluster_of_single_node() {
+		let mut nodes = BTreeMap::new();
+		let self_node_id = Random.generate().unwrap().public().clone();
+		nodes.insert(self_node_id, Random.generate().unwrap().secret().clone());
+		match SessionImpl::new(SessionParams {
+			meta: SessionMeta {
+				id: SessionId::default(),
+				self_node_id: self_node_id.clone(),
+				master_node_id: self_node_id.clone(),
+				threshold: 0,
+			},
+			access_key: Random.generate().unwrap().secret().clone(),
+			key_share: Some(DocumentKeyShare {
+				author: Public::default(),
+				threshold: 0,
+				public: Default::default(),
+				common_point: Some(Random.generate().unwrap().public().clone()),
+				encrypted_point: Some(Random.generate().unwrap().public().clone()),
+				versions: vec![DocumentKeyShareVersion {
+					hash: Default::default(),
+					id_numbers: nodes,
+					secret_share: Random.generate().unwrap().secret().clone(),
+				}],
+			}),
+			acl_storage: Arc::new(DummyAclStorage::default()),
+			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())) {
+			Ok(_) => (),
+			_ => panic!("unexpected"),
+		}
+	}
+
+	#[test]
+	fn fails_to_construct_if_access_key_equals_secret_share() {
+		let mut nodes = BTreeMap::new();
+		let self_node_id = Random.generate().unwrap().public().clone();
+		nodes.insert(self_node_id, Random.generate().unwrap().secret().clone());
+		let secret_share = Random.generate().unwrap().secret().clone();
+		match SessionImpl::new(SessionParams {
+			meta: SessionMeta {
+				id: SessionId::default(),
+				self_node_id: self_node_id.clone(),
+				master_node_id: self_node_id.clone(),
+				threshold: 0,
+			},
+			access_key: secret_share.clone(),
+			key_share: Some(DocumentKeyShare {
+				author: Public::default(),
+				threshold: 0,
+				public: Default::default(),
+				common_point: Some(Random.generate().unwrap().public().clone()),
+				encrypted_point: Some(Random.generate().unwrap().public().clone()),
+				versions: vec![DocumentKeyShareVersion {
+					hash: Default::default(),
+					id_numbers: nodes,
+					secret_share: secret_share,
+				}],
+			}),
+			acl_storage: Arc::new(DummyAclStorage::default()),
+			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())) {
+			Err(Error::InvalidMessage) => (),
+			_ => panic!("unexpected"),
+		}
+	}
+
+	#[test]
+	fn fails_to_initialize_if_does_not_have_a_share() {
+		let self_node_id = Random.generate().unwrap().public().clone();
+		let session = SessionImpl::new(SessionParams {
+			meta: SessionMeta {
+				id: SessionId::default(),
+				self_node_id: self_node_id.clone(),
+				master_node_id: self_node_id.clone(),
+				threshold: 0,
+			},
+			access_key: Random.generate().unwrap().secret().clone(),
+			key_share: None,
+			acl_storage: Arc::new(DummyAclStorage::default()),
+			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())).unwrap();
+		assert_eq!(session.initialize(Default::default(), 777.into()), Err(Error::InvalidMessage));
+	}
+
+	#[test]
+	fn fails_to_initialize_if_threshold_is_wrong() {
+		let mut nodes = BTreeMap::new();
+		let self_node_id = Random.generate().unwrap().public().clone();
+		nodes.insert(self_node_id.clone(), Random.generate().unwrap().secret().clone());
+		nodes.insert(Random.generate().unwrap().public().clone(), Random.generate().unwrap().secret().clone());
+		let session = SessionImpl::new(SessionParams {
+			meta: SessionMeta {
+				id: SessionId::default(),
+				self_node_id: self_node_id.clone(),
+				master_node_id: self_node_id.clone(),
+				threshold: 2,
+			},
+			access_key: Random.generate().unwrap().secret().clone(),
+			key_share: Some(DocumentKeyShare {
+				author: Public::default(),
+				threshold: 2,
+				public: Default::default(),
+				common_point: Some(Random.generate().unwrap().public().clone()),
+				encrypted_point: Some(Random.generate().unwrap().public().clone()),
+				versions: vec![DocumentKeyShareVersion {
+					hash: Default::default(),
+					id_numbers: nodes,
+					secret_share: Random.generate().unwrap().secret().clone(),
+				}],
+			}),
+			acl_storage: Arc::new(DummyAclStorage::default()),
+			cluster: Arc::new(DummyCluster::new(self_node_id.clone())),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(ethkey::sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap())).unwrap();
+		assert_eq!(session.initialize(Default::default(), 777.into()), Err(Error::ConsensusUnreachable {
+			retry_after: Some(Duration::from_secs(CONSENSUS_RETRY_BACKOFF * 2)),
+		}));
+	}
+
+	#[test]
+	fn fails_to_initialize_when_already_initialized() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Ok(()));
+		assert_eq!(sl.master().initialize(sl.version.clone(), 777.into()), Err(Error::InvalidStateForRequest));
+	}
+
+	#[test]
+	fn does_not_fail_when_consensus_message_received_after_consensus_established() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		// consensus is established
+		sl.run_until(|sl| sl.master().state() == SessionState::SessionKeyGeneration).unwrap();
+		// but 3rd node continues to send its messages
+		// this should not fail session
+		let consensus_group = sl.master().data.lock().consensus_session.select_consensus_group().unwrap().clone();
+		let mut had_3rd_message = false;
+		while let Some((from, to, message)) = sl.take_message() {
+			if !consensus_group.contains(&from) {
+				had_3rd_message = true;
+				sl.process_message((from, to, message)).unwrap();
+			}
+		}
+		assert!(had_3rd_message);
+	}
+
+	#[test]
+	fn late_consensus_confirmation_is_answered_with_completion() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// 3rd node confirms consensus after consensus group has been selected
+		let consensus_group = sl.master().data.lock().consensus_session.select_consensus_group().unwrap().clone();
+		let straggler = sl.nodes.keys().cloned().find(|n| !consensus_group.contains(n)).unwrap();
+		let (to, confirmation) = sl.nodes[&straggler].cluster.take_message().unwrap();
+		sl.process_message((straggler.clone(), to, confirmation)).unwrap();
+
+		// straggler is notified (and finishes its session) before signing is completed
+		while let Some((from, to, message)) = sl.take_message() {
+			let is_straggler_notification = to == straggler && match message {
+				Message::Signing(SigningMessage::SigningSessionCompleted(_)) => true,
+				_ => false,
+			};
+			sl.process_message((from, to, message)).unwrap();
+			if is_straggler_notification {
+				break;
+			}
+		}
+		assert!(sl.nodes[&straggler].session.is_finished());
+		assert!(sl.master().data.lock().result.is_none());
+
+		// and signing session is completed without straggler
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+	}
+
+	#[test]
+	fn master_waits_for_late_confirmation_when_full_consensus_wait_is_set() {
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			full_consensus_wait: Some(Duration::from_secs(60)),
+			..Default::default()
+		});
+		let master_id = sl.master().core.meta.self_node_id.clone();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// deliver consensus requests to slaves
+		for _ in 0..2 {
+			let (from, to, message) = sl.take_message().unwrap();
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		// first confirmation establishes consensus, but master waits for the second one
+		let (from, to, message) = sl.take_message().unwrap();
+		assert_eq!(to, master_id);
+		sl.process_message((from, to, message)).unwrap();
+		assert_eq!(sl.master().data.lock().consensus_session.state(), ConsensusSessionState::ConsensusEstablished);
+		assert_eq!(sl.master().state(), SessionState::ConsensusEstablishing);
+
+		// late confirmation is accepted && session key generation is started
+		let (from, to, message) = sl.take_message().unwrap();
+		assert_eq!(to, master_id);
+		sl.process_message((from, to, message)).unwrap();
+		assert_eq!(sl.master().state(), SessionState::SessionKeyGeneration);
+		assert_eq!(sl.master().data.lock().consensus_session.consensus_job().responders().len(), 3);
+
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait().is_ok());
+	}
+
+	#[test]
+	fn master_rejects_signature_computed_from_corrupted_partial_signature() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.master().core.meta.self_node_id.clone();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		let mut result = Ok(());
+		while let Some((from, to, mut message)) = sl.take_message() {
+			let is_partial_signature = match message {
+				Message::Signing(SigningMessage::PartialSignature(ref mut message)) => {
+					message.partial_signature = Random.generate().unwrap().secret().clone().into();
+					true
+				},
+				_ => false,
+			};
+
+			let message_result = sl.process_message((from, to.clone(), message));
+			if is_partial_signature && to == master_id {
+				result = message_result;
+				break;
+			}
+			message_result.unwrap();
+		}
+
+		// => master rejects aggregated signature && doesn't report completion
+		assert_eq!(result, Err(Error::InvalidMessage));
+		assert_eq!(sl.master().wait(), Err(Error::InvalidMessage));
+		while let Some((_, _, message)) = sl.take_message() {
+			match message {
+				Message::Signing(SigningMessage::SigningSessionCompleted(_)) => panic!("completion must not be reported"),
+				_ => (),
+			}
+		}
+	}
+
+	#[test]
+	fn master_rejects_confirmation_with_forged_signature() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.master().core.meta.self_node_id.clone();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// confirmation is signed by someone who is not the confirming node
+		let forger = Random.generate().unwrap();
+		let hash = confirmation_hash(&sl.session_id, &sl.master().core.access_key, true);
+		let forged_signature = ethkey::sign(forger.secret(), &hash).unwrap();
+
+		let mut result = Ok(());
+		let mut confirming_node = None;
+		while let Some((from, to, mut message)) = sl.take_message() {
+			let is_confirmation = match message {
+				Message::Signing(SigningMessage::SigningConsensusMessage(ref mut message)) => match message.message {
+					ConsensusMessage::ConfirmConsensusInitialization(ref mut message) => {
+						message.signature = Some(forged_signature.clone().into());
+						true
+					},
+					_ => false,
+				},
+				_ => false,
+			};
+
+			let message_result = sl.process_message((from.clone(), to.clone(), message));
+			if is_confirmation && to == master_id {
+				result = message_result;
+				confirming_node = Some(from);
+				break;
+			}
+			message_result.unwrap();
+		}
+
+		// => master rejects it
+		assert_eq!(result, Err(Error::InvalidMessage));
+		let confirming_node = confirming_node.unwrap();
+		assert!(!sl.master().data.lock().consensus_session.consensus_job().responders().contains(&confirming_node));
+	}
+
+	#[test]
+	fn master_accepts_confirmations_signed_by_slaves() {
+		let key_pairs: BTreeMap<_, _> = (0..3).map(|_| Random.generate().unwrap()).map(|key_pair| (key_pair.public().clone(), key_pair)).collect();
+		let gl = complete_key_generation(1, KeyGenerationMessageLoop::with_nodes_ids(key_pairs.keys().cloned().collect()));
+		let mut sl = MessageLoop::with_options(&gl, Random.generate().unwrap(), |i| SigningSessionOptions {
+			confirmation_key_pair: Some(Arc::new(PlainNodeKeyPair::new(key_pairs.values().nth(i).unwrap().clone()))),
+			require_signed_confirmations: true,
+			..Default::default()
+		});
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// every slave signs its confirmation && master accepts it
+		let mut signed_confirmations = 0;
+		while let Some((from, to, message)) = sl.take_message() {
+			if let Message::Signing(SigningMessage::SigningConsensusMessage(ref message)) = message {
+				if let ConsensusMessage::ConfirmConsensusInitialization(ref message) = message.message {
+					assert!(message.signature.is_some());
+					signed_confirmations += 1;
+				}
+			}
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		assert_eq!(signed_confirmations, 2);
+		assert!(sl.master().wait().is_ok());
+	}
+
+	#[test]
+	fn fails_when_consensus_message_is_received_when_not_initialized() {
 		let (_, sl) = prepare_signing_sessions(1, 3);
 		assert_eq!(sl.master().on_consensus_message(sl.nodes.keys().nth(1).unwrap(), &SigningConsensusMessage {
 			session: SessionId::default().into(),
@@ -1076,6 +4749,7 @@ mod tests {
 			session_nonce: 0,
 			message: ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 				is_confirmed: true,
+				signature: None,
 			}),
 		}), Err(Error::InvalidStateForRequest));
 	}
@@ -1119,6 +4793,44 @@ mod tests {
 		}), Err(Error::InvalidMessage));
 	}
 
+	#[test]
+	fn fails_when_consensus_group_does_not_include_self_node() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).unwrap().clone();
+		let slave1_id = sl.nodes.keys().nth(1).unwrap().clone();
+		let slave2_id = sl.nodes.keys().nth(2).unwrap().clone();
+		let consensus_group: BTreeSet<_> = vec![master_id.clone(), slave2_id.clone()].into_iter().collect();
+
+		// this node is never included in other nodes of consensus group
+		assert_eq!(sl.nodes[&slave1_id].session.core.other_consensus_group_nodes(&consensus_group), Err(Error::InvalidNodesConfiguration));
+		assert_eq!(sl.master().core.other_consensus_group_nodes(&consensus_group), Ok(vec![slave2_id.clone()].into_iter().collect()));
+
+		// => slave, which is not a member of consensus group, fails to start session key generation without sending anything
+		let slave1 = &sl.nodes[&slave1_id].session;
+		assert_eq!(slave1.on_generation_message(&master_id, &SigningGenerationMessage {
+			session: SessionId::default().into(),
+			sub_session: sl.master().core.access_key.clone().into(),
+			session_nonce: 0,
+			message: GenerationMessage::InitializeSession(InitializeSession {
+				session: SessionId::default().into(),
+				session_nonce: 0,
+				author: Public::default().into(),
+				nodes: consensus_group.iter().map(|n| (n.clone().into(), Random.generate().unwrap().secret().clone().into())).collect(),
+				threshold: 1,
+				derived_point: Public::default().into(),
+			})
+		}), Err(Error::InvalidNodesConfiguration));
+		assert!(sl.nodes[&slave1_id].cluster.take_message().is_none());
+	}
+
+	#[test]
+	fn fails_when_non_signing_message_is_received() {
+		let (_, sl) = prepare_signing_sessions(1, 3);
+		let slave1_id = sl.nodes.keys().nth(1).unwrap().clone();
+		assert_eq!(sl.master().on_message(&slave1_id, &Message::Cluster(ClusterMessage::KeepAlive(KeepAlive {}))),
+			Err(Error::InvalidMessage));
+	}
+
 	#[test]
 	fn fails_when_signature_requested_when_not_initialized() {
 		let (_, sl) = prepare_signing_sessions(1, 3);
@@ -1157,7 +4869,40 @@ mod tests {
 		sl.acl_storages[2].prohibit(sl.requester.public().clone(), SessionId::default());
 
 		// then consensus is unreachable
-		assert_eq!(sl.run_until(|_| false), Err(Error::ConsensusUnreachable));
+		assert_eq!(sl.run_until(|_| false), Err(Error::ConsensusUnreachable { retry_after: None }));
+	}
+
+	#[derive(Default)]
+	struct CollectingDenialListener(Mutex<Vec<SigningDenial>>);
+
+	impl DenialListener for CollectingDenialListener {
+		fn on_denied(&self, denial: &SigningDenial) {
+			self.0.lock().push(denial.clone());
+		}
+	}
+
+	#[test]
+	fn acl_denied_request_is_reported_to_denial_listener() {
+		let denial_listener = Arc::new(CollectingDenialListener::default());
+		let (_, mut sl) = prepare_signing_sessions_with_options(1, 3, |i| SigningSessionOptions {
+			denial_listener: if i == 0 { Some(denial_listener.clone()) } else { None },
+			..Default::default()
+		});
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// 2 of 3 nodes are rejecting access => consensus is unreachable
+		sl.acl_storages[1].prohibit(sl.requester.public().clone(), SessionId::default());
+		sl.acl_storages[2].prohibit(sl.requester.public().clone(), SessionId::default());
+		let error = sl.run_until(|_| false).unwrap_err();
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.master().on_session_error(&master_id, error);
+
+		let rejected_nodes: BTreeSet<_> = sl.nodes.keys().skip(1).cloned().collect();
+		assert_eq!(*denial_listener.0.lock(), vec![SigningDenial {
+			key_id: SessionId::default(),
+			requester: Some(ethkey::public_to_address(sl.requester.public())),
+			reason: DenialReason::Rejected(rejected_nodes),
+		}]);
 	}
 
 	#[test]
@@ -1181,6 +4926,334 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn session_uses_key_version_pinned_at_initialization() {
+		let (gl, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// concurrent re-share changes contents of the same key version in the storage
+		for node in sl.nodes.values() {
+			let mut key_share = node.key_storage.get(&SessionId::default()).unwrap().unwrap();
+			for version in key_share.versions.iter_mut() {
+				version.secret_share = Random.generate().unwrap().secret().clone();
+			}
+			node.key_storage.update(SessionId::default(), key_share).unwrap();
+		}
+
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &777.into()).unwrap());
+	}
+
+	#[test]
+	fn wait_with_timeout_returns_when_nonce_generation_is_stuck() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// nonce generation messages are never delivered
+		let started = Instant::now();
+		assert_eq!(sl.master().wait_with_timeout(Some(Duration::from_millis(100))), Err(Error::SessionTimeout));
+		assert!(started.elapsed() < Duration::from_secs(5));
+		assert!(sl.master().data.lock().result.is_none());
+	}
+
+	#[derive(Default)]
+	struct RecordingMathBackend {
+		calls: Mutex<Vec<&'static str>>,
+	}
+
+	impl MathBackend for RecordingMathBackend {
+		fn combine_message_hash_with_public(&self, message_hash: &H256, public: &Public) -> Result<Secret, Error> {
+			self.calls.lock().push("combine_message_hash_with_public");
+			DefaultMathBackend.combine_message_hash_with_public(message_hash, public)
+		}
+
+		fn compute_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+			node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error> {
+			self.calls.lock().push("compute_signature_share");
+			DefaultMathBackend.compute_signature_share(threshold, combined_hash, one_time_secret_coeff, node_secret_share, node_number, other_nodes_numbers)
+		}
+
+		fn compute_blinded_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+			node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error> {
+			self.calls.lock().push("compute_blinded_signature_share");
+			DefaultMathBackend.compute_blinded_signature_share(threshold, combined_hash, one_time_secret_coeff, node_secret_share, node_number, other_nodes_numbers)
+		}
+
+		fn compute_signature(&self, signature_shares: &[&Secret]) -> Result<Secret, Error> {
+			self.calls.lock().push("compute_signature");
+			DefaultMathBackend.compute_signature(signature_shares)
+		}
+	}
+
+	#[test]
+	fn custom_math_backend_is_used_for_signing_computations() {
+		let math_backend = Arc::new(RecordingMathBackend::default());
+		let (gl, mut sl) = prepare_signing_sessions_with_options(1, 3, |_| SigningSessionOptions {
+			math_backend: Some(math_backend.clone()),
+			..Default::default()
+		});
+
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		// signature, computed by custom backend, is the same as if it would be computed by default backend
+		let public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		let signature = sl.master().wait().unwrap();
+		assert!(math::verify_signature(&public, &signature, &777.into()).unwrap());
+		assert_eq!(signature.0, math::combine_message_hash_with_public(&777.into(), &sl.master().nonce_public().unwrap()).unwrap());
+
+		let calls = math_backend.calls.lock();
+		assert_eq!(calls.iter().filter(|c| **c == "compute_signature_share").count(), 2);
+		assert_eq!(calls.iter().filter(|c| **c == "compute_signature").count(), 1);
+		assert_eq!(calls.iter().filter(|c| **c == "combine_message_hash_with_public").count(), 3);
+	}
+
+	#[test]
+	fn early_partial_signature_request_is_requeued_on_slave() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+		let slave_id = sl.master().data.lock().nonce_generation_nodes.iter()
+			.find(|n| **n != master_id).cloned().unwrap();
+
+		// hold session key generation completion message, so that slave is still generating session key
+		let mut held_messages = Vec::new();
+		while let Some((from, to, message)) = sl.take_message() {
+			match message {
+				Message::Signing(SigningMessage::SigningGenerationMessage(SigningGenerationMessage {
+					message: GenerationMessage::SessionCompleted(_), ..
+				})) if to == slave_id => held_messages.push((from, to, message)),
+				_ => sl.process_message((from, to, message)).unwrap(),
+			}
+		}
+		assert_eq!(held_messages.len(), 1);
+		assert_eq!(sl.nodes[&slave_id].session.state(), SessionState::SessionKeyGeneration);
+
+		// partial signature request arrives earlier than slave completes session key generation
+		let request = Message::Signing(SigningMessage::RequestPartialSignature(RequestPartialSignature {
+			session: SessionId::default().into(),
+			sub_session: sl.master().core.access_key.clone().into(),
+			session_nonce: 0,
+			request_id: Random.generate().unwrap().secret().clone().into(),
+			message_hash: H256::from(777).into(),
+			nodes: vec![master_id.clone().into()].into_iter().collect(),
+		}));
+		assert_eq!(sl.nodes[&slave_id].session.on_message(&master_id, &request), Err(Error::TooEarlyForRequest));
+		sl.process_message((master_id.clone(), slave_id.clone(), request)).unwrap();
+		assert_eq!(sl.queue.len(), 1);
+		assert!(sl.nodes[&slave_id].session.data.lock().result.is_none());
+
+		// once session key is generated, requeued request is processed
+		sl.process_message(held_messages.pop().unwrap()).unwrap();
+		assert!(sl.queue.is_empty());
+		assert_eq!(sl.nodes[&slave_id].session.state(), SessionState::SignatureComputing);
+	}
+
+	#[test]
+	fn wait_until_reports_session_progress_on_expiry() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SignatureComputing {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// partial signature of master is already computed, but partial signature of other node is never delivered
+		assert_eq!(sl.master().wait_until(Instant::now() + Duration::from_millis(50)), Err(WaitTimeout {
+			state: SessionState::SignatureComputing,
+			partial_signatures_count: 1,
+		}));
+
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		assert!(sl.master().wait_until(Instant::now()).unwrap().is_ok());
+	}
+
+	#[test]
+	fn session_with_malformed_requester_signature_is_refused() {
+		let (_, sl) = prepare_signing_sessions(0, 1);
+		let node = sl.nodes.values().nth(0).unwrap();
+		let create_session = |requester_signature: Signature, acl_storage: DummyAclStorage| SessionImpl::new(SessionParams {
+			meta: node.session.core.meta.clone(),
+			access_key: Random.generate().unwrap().secret().clone(),
+			key_share: node.key_storage.get(&SessionId::default()).unwrap(),
+			acl_storage: Arc::new(acl_storage),
+			cluster: node.cluster.clone(),
+			nonce: 0,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: Default::default(),
+			options: Default::default(),
+		}, Some(requester_signature)).unwrap();
+
+		// signature, which can't be recovered to valid public, is refused as invalid
+		let session = create_session(Signature::default(), DummyAclStorage::default());
+		assert_eq!(session.initialize(sl.version.clone(), 777.into()), Err(Error::InvalidMessage));
+
+		// while valid requester without access is just denied by consensus
+		let acl_storage = DummyAclStorage::default();
+		acl_storage.prohibit(sl.requester.public().clone(), SessionId::default());
+		let session = create_session(ethkey::sign(sl.requester.secret(), &SessionId::default()).unwrap(), acl_storage);
+		assert_eq!(session.initialize(sl.version.clone(), 777.into()), Err(Error::ConsensusUnreachable { retry_after: None }));
+	}
+
+	#[test]
+	fn signature_is_reproduced_from_partials_and_lagrange_coefficients() {
+		let (gl, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		let signature = sl.master().wait().unwrap();
+
+		// coefficients are reconstructing joint secret from secret shares of signers
+		let coefficients = sl.master().lagrange_coefficients().unwrap();
+		assert_eq!(coefficients.keys().cloned().collect::<BTreeSet<_>>(), sl.master().signers().unwrap());
+		let weighted_shares: Vec<_> = coefficients.iter().map(|(signer, coeff)| {
+			let mut share = sl.nodes[signer].key_storage.get(&SessionId::default()).unwrap().unwrap()
+				.version(&sl.version).unwrap().secret_share.clone();
+			share.mul(coeff).unwrap();
+			share
+		}).collect();
+		let joint_secret = math::compute_secret_sum(weighted_shares.iter()).unwrap();
+		let joint_public = gl.master().joint_public_and_secret().unwrap().unwrap().0;
+		assert_eq!(math::compute_public_share(&joint_secret).unwrap(), joint_public);
+
+		// signature is the sum of partial signatures
+		let data = sl.master().data.lock();
+		let partial_signatures: Vec<_> = data.consensus_session.computation_responses().unwrap()
+			.values().map(|response| response.partial_signature.clone()).collect();
+		assert_eq!(math::compute_signature(partial_signatures.iter()).unwrap(), signature.1);
+	}
+
+	#[test]
+	fn session_progress_increases_monotonically() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		assert_eq!(sl.master().progress(), 0.0);
+
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		let mut progress = sl.master().progress();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+			let new_progress = sl.master().progress();
+			assert!(new_progress >= progress);
+			assert!(new_progress <= 1.0);
+			progress = new_progress;
+		}
+
+		assert!(sl.master().wait().is_ok());
+		assert_eq!(sl.master().progress(), 1.0);
+	}
+
+	#[test]
+	fn signing_fails_fast_when_nonce_generation_group_shrinks_below_threshold() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SessionKeyGeneration {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// there are still 2-of-3 nodes agreed to sign, but nonce is being generated by other group
+		let master_id = sl.master().core.meta.self_node_id.clone();
+		let failed_node = sl.master().data.lock().nonce_generation_nodes.iter()
+			.find(|n| **n != master_id).cloned().unwrap();
+		sl.master().on_node_timeout(&failed_node);
+
+		assert!(sl.master().is_finished());
+		assert_eq!(sl.master().wait(), Err(Error::ConsensusUnreachable { retry_after: None }));
+	}
+
+	#[test]
+	fn nonce_contributors_and_signers_differ_when_node_fails_after_nonce_generation() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		let contributor_id = sl.nodes.keys().nth(1).cloned().unwrap();
+
+		// 3rd node rejects, so there is no node to re-disseminate job to
+		sl.acl_storages[2].prohibit(sl.requester.public().clone(), SessionId::default());
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while sl.master().state() != SessionState::SignatureComputing {
+			let message = sl.take_message().unwrap();
+			sl.process_message(message).unwrap();
+		}
+
+		// 2nd node has contributed to nonce generation, but fails before providing partial signature
+		while let Some((from, to, message)) = sl.take_message() {
+			if from != contributor_id && to != contributor_id {
+				sl.process_message((from, to, message)).unwrap();
+			}
+		}
+		sl.master().on_node_timeout(&contributor_id);
+
+		assert!(sl.master().wait().is_err());
+		assert_eq!(sl.master().nonce_contributors(), Some(vec![master_id.clone(), contributor_id].into_iter().collect()));
+		assert_eq!(sl.master().signers(), Some(vec![master_id].into_iter().collect()));
+	}
+
+	#[test]
+	fn nonce_public_is_committed_to_by_signature() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		assert_eq!(sl.master().nonce_public(), None);
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		// c = H(message_hash | R.x)
+		let (c, _) = sl.master().wait().unwrap();
+		let nonce_public = sl.master().nonce_public().unwrap();
+		assert_eq!(math::combine_message_hash_with_public(&777.into(), &nonce_public).unwrap(), c);
+	}
+
+	#[test]
+	fn session_key_shares_are_never_saved_to_key_storage() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let key_shares: Vec<_> = sl.nodes.values().map(|n| n.key_storage.get(&SessionId::default()).unwrap()).collect();
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+		sl.master().wait().unwrap();
+
+		// session keys are generated by ephemeral generation sessions => key shares are unchanged
+		let key_shares_after: Vec<_> = sl.nodes.values().map(|n| n.key_storage.get(&SessionId::default()).unwrap()).collect();
+		assert_eq!(key_shares, key_shares_after);
+	}
+
+	#[test]
+	fn nonce_contributors_and_signers_are_reported() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+		while let Some((from, to, message)) = sl.take_message() {
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		let nonce_contributors = sl.master().nonce_contributors().unwrap();
+		assert_eq!(nonce_contributors.len(), 2);
+		assert_eq!(sl.master().signers(), Some(nonce_contributors));
+	}
+
 	#[test]
 	fn complete_signing_session_with_acl_check_failed_on_master() {
 		let (_, mut sl) = prepare_signing_sessions(1, 3);
@@ -1202,6 +5275,22 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn signing_session_fails_when_lifetime_is_exceeded() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		let master_id = sl.nodes.keys().nth(0).cloned().unwrap();
+		sl.nodes.get_mut(&master_id).unwrap().session.core.max_lifetime = Some(Duration::from_millis(10));
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// session is idle for longer than its lifetime
+		thread::sleep(Duration::from_millis(50));
+
+		// => next message to master fails the session
+		assert_eq!(sl.run_until(|_| false), Err(Error::SessionTimeout));
+		assert!(sl.master().is_finished());
+		assert_eq!(sl.master().wait(), Err(Error::SessionTimeout));
+	}
+
 	#[test]
 	fn signing_message_fails_when_nonce_is_wrong() {
 		let (_, sl) = prepare_signing_sessions(1, 3);
@@ -1234,7 +5323,7 @@ mod tests {
 		);
 
 		// now let's try to do a decryption
-		sl.nodes[&requested_node].session.delegate(actual_master, version, Default::default()).unwrap();
+		sl.nodes[&requested_node].session.delegate(actual_master, version, 777.into()).unwrap();
 
 		// then consensus reachable, but single node will disagree
 		while let Some((from, to, message)) = sl.take_message() {
@@ -1242,6 +5331,135 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn delegating_node_rejects_signature_of_other_message() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+
+		// node1 doesn't have a share && delegates signing request to node0
+		let actual_master = sl.nodes.keys().nth(0).cloned().unwrap();
+		let requested_node = sl.nodes.keys().skip(1).nth(0).cloned().unwrap();
+		let version = sl.nodes[&actual_master].key_storage.get(&Default::default()).unwrap().unwrap().last_version().unwrap().hash.clone();
+		sl.nodes[&requested_node].key_storage.remove(&Default::default()).unwrap();
+		sl.nodes.get_mut(&requested_node).unwrap().session.core.key_share = None;
+		sl.nodes.get_mut(&requested_node).unwrap().session.core.meta.master_node_id = sl.nodes[&requested_node].session.core.meta.self_node_id.clone();
+		sl.nodes[&requested_node].session.data.lock().consensus_session.consensus_job_mut().executor_mut().set_requester_signature(
+			sl.nodes[&actual_master].session.data.lock().consensus_session.consensus_job().executor().requester_signature().unwrap().clone()
+		);
+		sl.nodes[&requested_node].session.delegate(actual_master, version, 777.into()).unwrap();
+
+		// delegate signs other message
+		let mut errors = Vec::new();
+		while let Some((from, to, mut message)) = sl.take_message() {
+			if let Message::Signing(SigningMessage::SigningSessionDelegation(ref mut delegation)) = message {
+				delegation.message_hash = H256::from(888).into();
+			}
+			if let Err(error) = sl.process_message((from, to, message)) {
+				errors.push(error);
+			}
+		}
+
+		// => delegating node detects the mismatch
+		assert_eq!(errors, vec![Error::InvalidMessage]);
+		assert_eq!(sl.nodes[&requested_node].session.wait(), Err(Error::InvalidMessage));
+	}
+
+	#[test]
+	fn delegating_node_accepts_completion_without_message_hash() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+
+		// node1 doesn't have a share && delegates signing request to node0
+		let actual_master = sl.nodes.keys().nth(0).cloned().unwrap();
+		let requested_node = sl.nodes.keys().skip(1).nth(0).cloned().unwrap();
+		let version = sl.nodes[&actual_master].key_storage.get(&Default::default()).unwrap().unwrap().last_version().unwrap().hash.clone();
+		sl.nodes[&requested_node].key_storage.remove(&Default::default()).unwrap();
+		sl.nodes.get_mut(&requested_node).unwrap().session.core.key_share = None;
+		sl.nodes.get_mut(&requested_node).unwrap().session.core.meta.master_node_id = sl.nodes[&requested_node].session.core.meta.self_node_id.clone();
+		sl.nodes[&requested_node].session.data.lock().consensus_session.consensus_job_mut().executor_mut().set_requester_signature(
+			sl.nodes[&actual_master].session.data.lock().consensus_session.consensus_job().executor().requester_signature().unwrap().clone()
+		);
+		sl.nodes[&requested_node].session.delegate(actual_master, version, 777.into()).unwrap();
+
+		// delegate is running older version, which doesn't report signed message hash
+		while let Some((from, to, mut message)) = sl.take_message() {
+			if let Message::Signing(SigningMessage::SigningSessionDelegationCompleted(ref mut completed)) = message {
+				completed.message_hash = None;
+			}
+			sl.process_message((from, to, message)).unwrap();
+		}
+
+		// => signature is accepted
+		assert!(sl.nodes[&requested_node].session.wait().is_ok());
+	}
+
+	#[test]
+	fn delegated_session_is_cancelled_by_delegating_node() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+
+		// node1 doesn't have a share && delegates signing request to node0
+		let actual_master = sl.nodes.keys().nth(0).cloned().unwrap();
+		let requested_node = sl.nodes.keys().skip(1).nth(0).cloned().unwrap();
+		let version = sl.nodes[&actual_master].key_storage.get(&Default::default()).unwrap().unwrap().last_version().unwrap().hash.clone();
+		sl.nodes[&requested_node].key_storage.remove(&Default::default()).unwrap();
+		sl.nodes.get_mut(&requested_node).unwrap().session.core.key_share = None;
+		sl.nodes.get_mut(&requested_node).unwrap().session.core.meta.master_node_id = sl.nodes[&requested_node].session.core.meta.self_node_id.clone();
+		sl.nodes[&requested_node].session.data.lock().consensus_session.consensus_job_mut().executor_mut().set_requester_signature(
+			sl.nodes[&actual_master].session.data.lock().consensus_session.consensus_job().executor().requester_signature().unwrap().clone()
+		);
+		sl.nodes[&requested_node].session.delegate(actual_master.clone(), version, 777.into()).unwrap();
+
+		// deliver delegation message && cancel delegated session
+		let (from, to, message) = sl.take_message().unwrap();
+		assert_eq!((&from, &to), (&requested_node, &actual_master));
+		sl.process_message((from, to, message)).unwrap();
+		sl.nodes[&requested_node].session.cancel().unwrap();
+		assert_eq!(sl.nodes[&requested_node].session.cancel(), Err(Error::InvalidStateForRequest));
+
+		// other nodes are failing session, started by delegate, with an error
+		// && delegating node receives acknowledgement instead of error
+		while let Some((from, to, message)) = sl.take_message() {
+			if to == requested_node {
+				match message {
+					Message::Signing(SigningMessage::SigningSessionDelegationCancel(_)) => (),
+					_ => panic!("unexpected message to delegating node: {}", message),
+				}
+			}
+			let _ = sl.process_message((from, to, message));
+		}
+
+		assert_eq!(sl.nodes[&actual_master].session.wait(), Err(Error::Cancelled));
+		assert_eq!(sl.nodes[&requested_node].session.wait(), Err(Error::Cancelled));
+		assert!(sl.nodes[&actual_master].session.data.lock().delegation_status.is_none());
+		assert!(sl.nodes[&requested_node].session.data.lock().delegation_status.is_none());
+	}
+
+	#[test]
+	fn session_is_cancelled_by_master_during_nonce_generation() {
+		let (_, mut sl) = prepare_signing_sessions(1, 3);
+		sl.master().initialize(sl.version.clone(), 777.into()).unwrap();
+
+		// run until master starts session key generation
+		sl.run_until(|sl| sl.master().state() == SessionState::SessionKeyGeneration).unwrap();
+
+		// cancel session on master
+		let master_id = sl.master().core.meta.self_node_id.clone();
+		sl.master().cancel().unwrap();
+		assert_eq!(sl.master().cancel(), Err(Error::InvalidStateForRequest));
+		assert_eq!(sl.master().wait(), Err(Error::Cancelled));
+
+		// deliver all remaining messages (including cancellation) to peers
+		while let Some((from, to, message)) = sl.take_message() {
+			let _ = sl.process_message((from, to, message));
+		}
+
+		// => peers from nonce generation group observe the error
+		let peers: Vec<_> = sl.master().data.lock().consensus_session.consensus_non_rejected_nodes().into_iter().collect();
+		assert!(!peers.is_empty());
+		for peer in peers {
+			assert!(peer != master_id);
+			assert!(sl.nodes[&peer].session.wait().is_err());
+		}
+	}
+
 	#[test]
 	fn signing_works_when_share_owners_are_isolated() {
 		let (_, mut sl) = prepare_signing_sessions(1, 3);
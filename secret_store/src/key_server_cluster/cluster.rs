@@ -28,15 +28,15 @@ use tokio_core::reactor::{Handle, Remote, Interval};
 use tokio_core::net::{TcpListener, TcpStream};
 use ethkey::{Public, KeyPair, Signature, Random, Generator};
 use ethereum_types::H256;
-use key_server_cluster::{Error, NodeId, SessionId, AclStorage, KeyStorage, KeyServerSet, NodeKeyPair};
+use key_server_cluster::{Error, NodeId, SessionId, AclStorageHandle, KeyStorage, KeyServerSet, NodeKeyPair};
 use key_server_cluster::cluster_sessions::{ClusterSession, AdminSession, ClusterSessions, SessionIdWithSubSession,
-	ClusterSessionsContainer, SERVERS_SET_CHANGE_SESSION_ID, create_cluster_view, AdminSessionCreationData, ClusterSessionsListener};
+	ClusterSessionsContainer, SERVERS_SET_CHANGE_SESSION_ID, create_cluster_view, AdminSessionCreationData, SigningSessionCreationData, ClusterSessionsListener};
 use key_server_cluster::cluster_sessions_creator::{ClusterSessionCreator, IntoSessionId};
 use key_server_cluster::message::{self, Message, ClusterMessage};
 use key_server_cluster::generation_session::{SessionImpl as GenerationSession};
 use key_server_cluster::decryption_session::{SessionImpl as DecryptionSession};
 use key_server_cluster::encryption_session::{SessionImpl as EncryptionSession};
-use key_server_cluster::signing_session::{SessionImpl as SigningSession};
+use key_server_cluster::signing_session::{SessionImpl as SigningSession, SigningSessionOptions, SigningRequestOptions};
 use key_server_cluster::key_version_negotiation_session::{SessionImpl as KeyVersionNegotiationSession,
 	IsolatedSessionTransport as KeyVersionNegotiationSessionTransport, ContinueAction};
 use key_server_cluster::io::{DeadlineStatus, ReadMessage, SharedTcpStream, read_encrypted_message, WriteMessage, write_encrypted_message};
@@ -71,7 +71,7 @@ pub trait ClusterClient: Send + Sync {
 	/// Start new decryption session.
 	fn new_decryption_session(&self, session_id: SessionId, requestor_signature: Signature, version: Option<H256>, is_shadow_decryption: bool) -> Result<Arc<DecryptionSession>, Error>;
 	/// Start new signing session.
-	fn new_signing_session(&self, session_id: SessionId, requestor_signature: Signature, version: Option<H256>, message_hash: H256) -> Result<Arc<SigningSession>, Error>;
+	fn new_signing_session(&self, session_id: SessionId, requestor_signature: Signature, version: Option<H256>, message_hash: H256, request_options: SigningRequestOptions) -> Result<Arc<SigningSession>, Error>;
 	/// Start new key version negotiation session.
 	fn new_key_version_negotiation_session(&self, session_id: SessionId) -> Result<Arc<KeyVersionNegotiationSession<KeyVersionNegotiationSessionTransport>>, Error>;
 	/// Start new servers set change session.
@@ -122,12 +122,14 @@ pub struct ClusterConfiguration {
 	/// Reference to key storage
 	pub key_storage: Arc<KeyStorage>,
 	/// Reference to ACL storage
-	pub acl_storage: Arc<AclStorage>,
+	pub acl_storage: Arc<AclStorageHandle>,
 	/// Administrator public key.
 	pub admin_public: Option<Public>,
 	/// Should key servers set change session should be started when servers set changes.
 	/// This will only work when servers set is configured using KeyServerSet contract.
 	pub auto_migrate_enabled: bool,
+	/// Options of signing sessions, started or joined by this node.
+	pub signing_session_options: SigningSessionOptions,
 }
 
 /// Cluster state.
@@ -873,6 +875,39 @@ impl ClusterClientImpl {
 			}
 		}
 	}
+
+	fn start_signing_session(&self, session_id: SessionId, requestor_signature: Signature, version: Option<H256>, message_hash: H256, request_options: SigningRequestOptions) -> Result<Arc<SigningSession>, Error> {
+		let mut connected_nodes = self.data.connections.connected_nodes();
+		connected_nodes.insert(self.data.self_key_pair.public().clone());
+
+		let access_key = Random.generate()?.secret().clone();
+		let session_id = SessionIdWithSubSession::new(session_id, access_key);
+		let cluster = create_cluster_view(&self.data, false)?;
+		let creation_data = Some(SigningSessionCreationData {
+			requester_signature: requestor_signature,
+			request_options: request_options,
+		});
+		let session = self.data.sessions.signing_sessions.insert(cluster, self.data.self_key_pair.public().clone(), session_id.clone(), None, false, creation_data)?;
+
+		let initialization_result = match version {
+			Some(version) => session.initialize(version, message_hash),
+			None => {
+				self.create_key_version_negotiation_session(session_id.id.clone())
+					.map(|version_session| {
+						version_session.set_continue_action(ContinueAction::Sign(session.clone(), message_hash));
+						ClusterCore::try_continue_session(&self.data, Some(version_session));
+					})
+			},
+		};
+
+		match initialization_result {
+			Ok(()) => Ok(session),
+			Err(error) => {
+				self.data.sessions.signing_sessions.remove(&session.id());
+				Err(error)
+			},
+		}
+	}
 }
 
 impl ClusterClient for ClusterClientImpl {
@@ -939,32 +974,11 @@ impl ClusterClient for ClusterClientImpl {
 		}
 	}
 
-	fn new_signing_session(&self, session_id: SessionId, requestor_signature: Signature, version: Option<H256>, message_hash: H256) -> Result<Arc<SigningSession>, Error> {
-		let mut connected_nodes = self.data.connections.connected_nodes();
-		connected_nodes.insert(self.data.self_key_pair.public().clone());
-
-		let access_key = Random.generate()?.secret().clone();
-		let session_id = SessionIdWithSubSession::new(session_id, access_key);
-		let cluster = create_cluster_view(&self.data, false)?;
-		let session = self.data.sessions.signing_sessions.insert(cluster, self.data.self_key_pair.public().clone(), session_id.clone(), None, false, Some(requestor_signature))?;
-
-		let initialization_result = match version {
-			Some(version) => session.initialize(version, message_hash),
-			None => {
-				self.create_key_version_negotiation_session(session_id.id.clone())
-					.map(|version_session| {
-						version_session.set_continue_action(ContinueAction::Sign(session.clone(), message_hash));
-						ClusterCore::try_continue_session(&self.data, Some(version_session));
-					})
-			},
-		};
-
-		match initialization_result {
-			Ok(()) => Ok(session),
-			Err(error) => {
-				self.data.sessions.signing_sessions.remove(&session.id());
-				Err(error)
-			},
+	fn new_signing_session(&self, session_id: SessionId, requestor_signature: Signature, version: Option<H256>, message_hash: H256, request_options: SigningRequestOptions) -> Result<Arc<SigningSession>, Error> {
+		match version {
+			Some(version) => self.data.sessions.signing_requests.get_or_start(session_id.clone(), requestor_signature.clone(), version.clone(), message_hash.clone(),
+				|| self.start_signing_session(session_id, requestor_signature, Some(version), message_hash, request_options)),
+			None => self.start_signing_session(session_id, requestor_signature, None, message_hash, request_options),
 		}
 	}
 
@@ -1026,6 +1040,28 @@ impl ClusterClient for ClusterClientImpl {
 	}
 }
 
+/// Start new signing sessions for the same message hash under every given key version.
+/// If any of sessions fails to start, sessions that have been already started are cancelled.
+pub fn new_multi_version_signing_session(client: &ClusterClient, session_id: SessionId, requestor_signature: Signature, versions: BTreeSet<H256>, message_hash: H256, request_options: SigningRequestOptions) -> Result<BTreeMap<H256, Arc<SigningSession>>, Error> {
+	let mut sessions = BTreeMap::new();
+	for version in versions {
+		match client.new_signing_session(session_id.clone(), requestor_signature.clone(), Some(version.clone()), message_hash.clone(), request_options.clone()) {
+			Ok(session) => {
+				sessions.insert(version, session);
+			},
+			Err(error) => {
+				for session in sessions.values() {
+					// session could have been already completed => ignore error
+					let _ = session.cancel();
+				}
+				return Err(error);
+			},
+		}
+	}
+
+	Ok(sessions)
+}
+
 fn make_socket_address(address: &str, port: u16) -> Result<SocketAddr, Error> {
 	let ip_address: IpAddr = address.parse().map_err(|_| Error::InvalidNodeAddress)?;
 	Ok(SocketAddr::new(ip_address, port))
@@ -1040,14 +1076,14 @@ pub mod tests {
 	use tokio_core::reactor::Core;
 	use ethereum_types::H256;
 	use ethkey::{Random, Generator, Public, Signature, sign};
-	use key_server_cluster::{NodeId, SessionId, Error, DummyAclStorage, DummyKeyStorage, MapKeyServerSet, PlainNodeKeyPair, KeyStorage};
+	use key_server_cluster::{NodeId, SessionId, Error, DummyAclStorage, AclStorageHandle, DummyKeyStorage, MapKeyServerSet, PlainNodeKeyPair, KeyStorage};
 	use key_server_cluster::message::Message;
-	use key_server_cluster::cluster::{Cluster, ClusterCore, ClusterConfiguration, ClusterClient, ClusterState};
+	use key_server_cluster::cluster::{Cluster, ClusterCore, ClusterConfiguration, ClusterClient, ClusterState, new_multi_version_signing_session};
 	use key_server_cluster::cluster_sessions::{ClusterSession, AdminSession, ClusterSessionsListener};
 	use key_server_cluster::generation_session::{SessionImpl as GenerationSession, SessionState as GenerationSessionState};
 	use key_server_cluster::decryption_session::{SessionImpl as DecryptionSession};
 	use key_server_cluster::encryption_session::{SessionImpl as EncryptionSession};
-	use key_server_cluster::signing_session::{SessionImpl as SigningSession};
+	use key_server_cluster::signing_session::{SessionImpl as SigningSession, SigningRequestOptions};
 	use key_server_cluster::key_version_negotiation_session::{SessionImpl as KeyVersionNegotiationSession,
 		IsolatedSessionTransport as KeyVersionNegotiationSessionTransport};
 
@@ -1063,6 +1099,8 @@ pub mod tests {
 	#[derive(Debug, Default)]
 	struct DummyClusterData {
 		nodes: BTreeSet<NodeId>,
+		unreachable_nodes: BTreeSet<NodeId>,
+		send_failure: Option<(f64, u64)>,
 		messages: VecDeque<(NodeId, Message)>,
 	}
 
@@ -1071,7 +1109,7 @@ pub mod tests {
 		fn new_generation_session(&self, _session_id: SessionId, _author: Public, _threshold: usize) -> Result<Arc<GenerationSession>, Error> { unimplemented!("test-only") }
 		fn new_encryption_session(&self, _session_id: SessionId, _requestor_signature: Signature, _common_point: Public, _encrypted_point: Public) -> Result<Arc<EncryptionSession>, Error> { unimplemented!("test-only") }
 		fn new_decryption_session(&self, _session_id: SessionId, _requestor_signature: Signature, _version: Option<H256>, _is_shadow_decryption: bool) -> Result<Arc<DecryptionSession>, Error> { unimplemented!("test-only") }
-		fn new_signing_session(&self, _session_id: SessionId, _requestor_signature: Signature, _version: Option<H256>, _message_hash: H256) -> Result<Arc<SigningSession>, Error> { unimplemented!("test-only") }
+		fn new_signing_session(&self, _session_id: SessionId, _requestor_signature: Signature, _version: Option<H256>, _message_hash: H256, _request_options: SigningRequestOptions) -> Result<Arc<SigningSession>, Error> { unimplemented!("test-only") }
 		fn new_key_version_negotiation_session(&self, _session_id: SessionId) -> Result<Arc<KeyVersionNegotiationSession<KeyVersionNegotiationSessionTransport>>, Error> { unimplemented!("test-only") }
 		fn new_servers_set_change_session(&self, _session_id: Option<SessionId>, _migration_id: Option<H256>, _new_nodes_set: BTreeSet<NodeId>, _old_set_signature: Signature, _new_set_signature: Signature) -> Result<Arc<AdminSession>, Error> { unimplemented!("test-only") }
 
@@ -1107,11 +1145,36 @@ pub mod tests {
 			self.data.lock().nodes.remove(node);
 		}
 
+		pub fn make_unreachable(&self, node: NodeId) {
+			self.data.lock().unreachable_nodes.insert(node);
+		}
+
+		/// Make every send fail with given probability. Failures are deterministic for the given seed.
+		pub fn set_send_failure_probability(&self, probability: f64, seed: u64) {
+			self.data.lock().send_failure = Some((probability, seed.wrapping_add(0x9e3779b97f4a7c15) | 1));
+		}
+
 		pub fn take_message(&self) -> Option<(NodeId, Message)> {
 			self.data.lock().messages.pop_front()
 		}
 	}
 
+	impl DummyClusterData {
+		fn is_send_failing(&mut self) -> bool {
+			match self.send_failure.as_mut() {
+				Some(&mut (probability, ref mut state)) => {
+					// xorshift64*
+					*state ^= *state >> 12;
+					*state ^= *state << 25;
+					*state ^= *state >> 27;
+					let random = state.wrapping_mul(0x2545f4914f6cdd1d) >> 11;
+					(random as f64) / ((1u64 << 53) as f64) < probability
+				},
+				None => false,
+			}
+		}
+	}
+
 	impl Cluster for DummyCluster {
 		fn broadcast(&self, message: Message) -> Result<(), Error> {
 			let mut data = self.data.lock();
@@ -1124,7 +1187,12 @@ pub mod tests {
 
 		fn send(&self, to: &NodeId, message: Message) -> Result<(), Error> {
 			debug_assert!(&self.id != to);
-			self.data.lock().messages.push_back((to.clone(), message));
+			let mut data = self.data.lock();
+			if data.unreachable_nodes.contains(to) || data.is_send_failing() {
+				return Err(Error::NodeDisconnected);
+			}
+
+			data.messages.push_back((to.clone(), message));
 			Ok(())
 		}
 
@@ -1169,9 +1237,10 @@ pub mod tests {
 				.collect())),
 			allow_connecting_to_higher_nodes: false,
 			key_storage: Arc::new(DummyKeyStorage::default()),
-			acl_storage: Arc::new(DummyAclStorage::default()),
+			acl_storage: Arc::new(AclStorageHandle::new(Arc::new(DummyAclStorage::default()))),
 			admin_public: None,
 			auto_migrate_enabled: false,
+			signing_session_options: Default::default(),
 		}).collect();
 		let clusters: Vec<_> = cluster_params.into_iter().enumerate()
 			.map(|(_, params)| ClusterCore::new(core.handle(), params).unwrap())
@@ -1349,7 +1418,7 @@ pub mod tests {
 
 		// and try to sign message with generated key
 		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
-		let session0 = clusters[0].client().new_signing_session(Default::default(), signature, None, Default::default()).unwrap();
+		let session0 = clusters[0].client().new_signing_session(Default::default(), signature, None, 777.into(), Default::default()).unwrap();
 		let session = clusters[0].data.sessions.signing_sessions.first().unwrap();
 
 		loop_until(&mut core, time::Duration::from_millis(300), || session.is_finished() && (0..3).all(|i|
@@ -1358,7 +1427,7 @@ pub mod tests {
 
 		// and try to sign message with generated key using node that has no key share
 		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
-		let session2 = clusters[2].client().new_signing_session(Default::default(), signature, None, Default::default()).unwrap();
+		let session2 = clusters[2].client().new_signing_session(Default::default(), signature, None, 777.into(), Default::default()).unwrap();
 		let session = clusters[2].data.sessions.signing_sessions.first().unwrap();
 		loop_until(&mut core, time::Duration::from_millis(300), || session.is_finished()  && (0..3).all(|i|
 			clusters[i].data.sessions.signing_sessions.is_empty()));
@@ -1369,9 +1438,64 @@ pub mod tests {
 
 		// and try to sign message with generated key
 		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
-		let session1 = clusters[0].client().new_signing_session(Default::default(), signature, None, Default::default()).unwrap();
+		let session1 = clusters[0].client().new_signing_session(Default::default(), signature, None, 777.into(), Default::default()).unwrap();
 		let session = clusters[0].data.sessions.signing_sessions.first().unwrap();
 		loop_until(&mut core, time::Duration::from_millis(300), || session.is_finished());
 		session1.wait().unwrap_err();
 	}
+
+	#[test]
+	fn identical_signing_requests_are_served_by_single_session() {
+		//::logger::init_log();
+		let mut core = Core::new().unwrap();
+		let clusters = make_clusters(&core, 6046, 3);
+		run_clusters(&clusters);
+		loop_until(&mut core, time::Duration::from_millis(300), || clusters.iter().all(all_connections_established));
+
+		// start && wait for generation session to complete
+		let session = clusters[0].client().new_generation_session(SessionId::default(), Public::default(), 1).unwrap();
+		loop_until(&mut core, time::Duration::from_millis(300), || (session.state() == GenerationSessionState::Finished
+			|| session.state() == GenerationSessionState::Failed)
+			&& clusters[0].client().generation_session(&SessionId::default()).is_none());
+		assert!(session.joint_public_and_secret().unwrap().is_ok());
+
+		// start two identical signing requests
+		let version = clusters[0].data.config.key_storage.get(&Default::default()).unwrap().unwrap().last_version().unwrap().hash.clone();
+		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
+		let session0 = clusters[0].client().new_signing_session(Default::default(), signature.clone(), Some(version.clone()), 777.into(), Default::default()).unwrap();
+		let session1 = clusters[0].client().new_signing_session(Default::default(), signature, Some(version), 777.into(), Default::default()).unwrap();
+		assert!(Arc::ptr_eq(&session0, &session1));
+
+		// only one signing protocol is running && both requesters are receiving the same signature
+		loop_until(&mut core, time::Duration::from_millis(300), || session0.is_finished() && (0..3).all(|i|
+			clusters[i].data.sessions.signing_sessions.is_empty()));
+		assert_eq!(session0.wait().unwrap(), session1.wait().unwrap());
+	}
+
+	#[test]
+	fn multi_version_signing_cancels_started_sessions_on_error() {
+		//::logger::init_log();
+		let mut core = Core::new().unwrap();
+		let clusters = make_clusters(&core, 6052, 3);
+		run_clusters(&clusters);
+		loop_until(&mut core, time::Duration::from_millis(300), || clusters.iter().all(all_connections_established));
+
+		// start && wait for generation session to complete
+		let session = clusters[0].client().new_generation_session(SessionId::default(), Public::default(), 1).unwrap();
+		loop_until(&mut core, time::Duration::from_millis(300), || (session.state() == GenerationSessionState::Finished
+			|| session.state() == GenerationSessionState::Failed)
+			&& clusters[0].client().generation_session(&SessionId::default()).is_none());
+		assert!(session.joint_public_and_secret().unwrap().is_ok());
+
+		// session for existing version is started first, session for unknown version fails to start
+		let version = clusters[0].data.config.key_storage.get(&Default::default()).unwrap().unwrap().last_version().unwrap().hash.clone();
+		let unknown_version = H256([0xff; 32]);
+		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
+		assert!(new_multi_version_signing_session(&*clusters[0].client(), Default::default(), signature,
+			vec![version, unknown_version].into_iter().collect(), 777.into(), Default::default()).is_err());
+
+		// => session for existing version is cancelled
+		let session = clusters[0].data.sessions.signing_sessions.first().unwrap();
+		assert_eq!(session.wait(), Err(Error::Cancelled));
+	}
 }
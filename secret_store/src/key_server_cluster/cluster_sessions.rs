@@ -16,8 +16,8 @@
 
 use std::time;
 use std::sync::{Arc, Weak};
-use std::sync::atomic::AtomicBool;
-use std::collections::{VecDeque, BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::collections::{VecDeque, BTreeMap, BTreeSet, HashMap};
 use parking_lot::{Mutex, RwLock, Condvar};
 use ethereum_types::H256;
 use ethkey::{Secret, Signature};
@@ -28,7 +28,7 @@ use key_server_cluster::message::{self, Message};
 use key_server_cluster::generation_session::{SessionImpl as GenerationSessionImpl};
 use key_server_cluster::decryption_session::{SessionImpl as DecryptionSessionImpl};
 use key_server_cluster::encryption_session::{SessionImpl as EncryptionSessionImpl};
-use key_server_cluster::signing_session::{SessionImpl as SigningSessionImpl};
+use key_server_cluster::signing_session::{SessionImpl as SigningSessionImpl, SigningRequestOptions};
 use key_server_cluster::share_add_session::{SessionImpl as ShareAddSessionImpl, IsolatedSessionTransport as ShareAddTransport};
 use key_server_cluster::servers_set_change_session::{SessionImpl as ServersSetChangeSessionImpl};
 use key_server_cluster::key_version_negotiation_session::{SessionImpl as KeyVersionNegotiationSessionImpl,
@@ -44,6 +44,9 @@ use key_server_cluster::cluster_sessions_creator::{GenerationSessionCreator, Enc
 const SESSION_TIMEOUT_INTERVAL: u64 = 60;
 /// Interval to send session-level KeepAlive-messages.
 const SESSION_KEEP_ALIVE_INTERVAL: u64 = 30;
+/// Maximal number of messages, which could be queued for the single session.
+/// When this limit is reached, oldest messages are dropped from the queue.
+const MAX_SESSION_QUEUE_SIZE: usize = 1024;
 
 lazy_static! {
 	/// Servers set change session id (there could be at most 1 session => hardcoded id).
@@ -86,16 +89,17 @@ pub trait ClusterSession {
 		let mut locked_data = session_data.lock();
 		match result_reader(&locked_data) {
 			Some(result) => result,
-			None => {
-				match timeout {
-					None => completion_event.wait(&mut locked_data),
-					Some(timeout) => {
-						completion_event.wait_for(&mut locked_data, timeout);
-					},
-				}
-
-				result_reader(&locked_data)
-					.expect("waited for completion; completion is only signaled when result.is_some(); qed")
+			None => match timeout {
+				None => {
+					completion_event.wait(&mut locked_data);
+					result_reader(&locked_data)
+						.expect("waited for completion; completion is only signaled when result.is_some(); qed")
+				},
+				Some(timeout) => {
+					completion_event.wait_for(&mut locked_data, timeout);
+					// no result => session has not been completed in time
+					result_reader(&locked_data).unwrap_or(Err(Error::SessionTimeout))
+				},
 			},
 		}
 	}
@@ -117,6 +121,14 @@ pub enum AdminSessionCreationData {
 	ServersSetChange(Option<H256>, BTreeSet<NodeId>),
 }
 
+/// Signing session creation data.
+pub struct SigningSessionCreationData {
+	/// Requester signature.
+	pub requester_signature: Signature,
+	/// Options of signing request. Default on nodes, which are not starting the session.
+	pub request_options: SigningRequestOptions,
+}
+
 /// Active sessions on this cluster.
 pub struct ClusterSessions {
 	/// Key generation sessions.
@@ -126,7 +138,9 @@ pub struct ClusterSessions {
 	/// Decryption sessions.
 	pub decryption_sessions: ClusterSessionsContainer<DecryptionSessionImpl, DecryptionSessionCreator, Signature>,
 	/// Signing sessions.
-	pub signing_sessions: ClusterSessionsContainer<SigningSessionImpl, SigningSessionCreator, Signature>,
+	pub signing_sessions: ClusterSessionsContainer<SigningSessionImpl, SigningSessionCreator, SigningSessionCreationData>,
+	/// In-flight signing sessions, started by this node.
+	pub signing_requests: SigningRequests,
 	/// Key version negotiation sessions.
 	pub negotiation_sessions: ClusterSessionsContainer<KeyVersionNegotiationSessionImpl<VersionNegotiationTransport>, KeyVersionNegotiationSessionCreator, ()>,
 	/// Administrative sessions.
@@ -137,6 +151,32 @@ pub struct ClusterSessions {
 	creator_core: Arc<SessionCreatorCore>,
 }
 
+/// In-flight signing sessions, started by this node, keyed by request parameters.
+/// Identical concurrent signing requests are attached to the same session.
+#[derive(Default)]
+pub struct SigningRequests {
+	/// Sessions, keyed by key id, requester signature, key version and message hash.
+	sessions: Mutex<HashMap<(SessionId, Signature, H256, H256), SigningRequest>>,
+}
+
+/// Signing request, registered in the signing requests registry.
+enum SigningRequest {
+	/// Session is being started. Session is started outside of registry lock, so that
+	/// unrelated requests are not waiting for it.
+	Starting(Arc<PendingSigningRequest>),
+	/// Session has been started.
+	Started(Weak<SigningSessionImpl>),
+}
+
+/// Signing request, which session is being started.
+#[derive(Default)]
+struct PendingSigningRequest {
+	/// Result of session start.
+	result: Mutex<Option<Result<Arc<SigningSessionImpl>, Error>>>,
+	/// Signalled when session start is completed.
+	started: Condvar,
+}
+
 /// Active sessions container listener.
 pub trait ClusterSessionsListener<S: ClusterSession>: Send + Sync {
 	/// When new session is inserted to the container.
@@ -155,6 +195,8 @@ pub struct ClusterSessionsContainer<S: ClusterSession, SC: ClusterSessionCreator
 	listeners: Mutex<Vec<Weak<ClusterSessionsListener<S>>>>,
 	/// Sessions container state.
 	container_state: Arc<Mutex<ClusterSessionsContainerState>>,
+	/// Number of messages, dropped because of session queue overflow.
+	dropped_messages: AtomicUsize,
 	/// Phantom data.
 	_pd: ::std::marker::PhantomData<D>,
 }
@@ -186,6 +228,94 @@ pub enum ClusterSessionsContainerState {
 	Exclusive,
 }
 
+impl SigningRequests {
+	/// Get in-flight session, started for the same request, or start new session.
+	pub fn get_or_start<F>(&self, key_id: SessionId, requester_signature: Signature, version: H256, message_hash: H256, start: F) -> Result<Arc<SigningSessionImpl>, Error>
+		where F: FnOnce() -> Result<Arc<SigningSessionImpl>, Error> {
+		let request = (key_id, requester_signature, version, message_hash);
+		let (pending, is_starting) = loop {
+			let existing_session = {
+				let mut sessions = self.sessions.lock();
+				sessions.retain(|_, request| match *request {
+					SigningRequest::Starting(_) => true,
+					SigningRequest::Started(ref session) => session.upgrade().is_some(),
+				});
+
+				let existing_session = match sessions.get(&request) {
+					Some(&SigningRequest::Started(ref session)) => session.upgrade(),
+					Some(&SigningRequest::Starting(ref pending)) => break (pending.clone(), false),
+					None => None,
+				};
+
+				match existing_session {
+					Some(existing_session) => existing_session,
+					None => {
+						let pending = Arc::new(PendingSigningRequest::default());
+						sessions.insert(request.clone(), SigningRequest::Starting(pending.clone()));
+						break (pending, true);
+					},
+				}
+			};
+
+			// session state is checked without holding requests lock, because it requires locking session data
+			if !existing_session.is_finished() {
+				return Ok(existing_session);
+			}
+
+			// finished session can't serve the request => forget it (unless it is already replaced) and retry
+			let mut sessions = self.sessions.lock();
+			let is_same_session = match sessions.get(&request) {
+				Some(&SigningRequest::Started(ref session)) => session.upgrade()
+					.map(|session| Arc::ptr_eq(&session, &existing_session))
+					.unwrap_or(false),
+				_ => false,
+			};
+			if is_same_session {
+				sessions.remove(&request);
+			}
+		};
+
+		// identical request is being started by other thread => wait until it is started
+		if !is_starting {
+			return pending.wait();
+		}
+
+		let result = start();
+		{
+			let mut sessions = self.sessions.lock();
+			match result {
+				Ok(ref session) => {
+					sessions.insert(request, SigningRequest::Started(Arc::downgrade(session)));
+				},
+				Err(_) => {
+					sessions.remove(&request);
+				},
+			}
+		}
+		pending.complete(result.clone());
+		result
+	}
+}
+
+impl PendingSigningRequest {
+	/// Wait until session is started.
+	fn wait(&self) -> Result<Arc<SigningSessionImpl>, Error> {
+		let mut result = self.result.lock();
+		loop {
+			if let Some(ref result) = *result {
+				return result.clone();
+			}
+			self.started.wait(&mut result);
+		}
+	}
+
+	/// Complete session start.
+	fn complete(&self, result: Result<Arc<SigningSessionImpl>, Error>) {
+		*self.result.lock() = Some(result);
+		self.started.notify_all();
+	}
+}
+
 impl ClusterSessions {
 	/// Create new cluster sessions container.
 	pub fn new(config: &ClusterConfiguration, servers_set_change_session_creator_connector: Arc<ServersSetChangeSessionCreatorConnector>) -> Self {
@@ -206,6 +336,7 @@ impl ClusterSessions {
 			signing_sessions: ClusterSessionsContainer::new(SigningSessionCreator {
 				core: creator_core.clone(),
 			}, container_state.clone()),
+			signing_requests: SigningRequests::default(),
 			negotiation_sessions: ClusterSessionsContainer::new(KeyVersionNegotiationSessionCreator {
 				core: creator_core.clone(),
 			}, container_state.clone()),
@@ -264,6 +395,7 @@ impl<S, SC, D> ClusterSessionsContainer<S, SC, D> where S: ClusterSession, SC: C
 			sessions: RwLock::new(BTreeMap::new()),
 			listeners: Mutex::new(Vec::new()),
 			container_state: container_state,
+			dropped_messages: AtomicUsize::new(0),
 			_pd: Default::default(),
 		}
 	}
@@ -293,6 +425,10 @@ impl<S, SC, D> ClusterSessionsContainer<S, SC, D> where S: ClusterSession, SC: C
 		self.sessions.read().values().nth(0).map(|s| s.session.clone())
 	}
 
+	pub fn filter<F>(&self, predicate: F) -> Vec<Arc<S>> where F: Fn(&S) -> bool {
+		self.sessions.read().values().filter(|s| predicate(&s.session)).map(|s| s.session.clone()).collect()
+	}
+
 	pub fn insert(&self, cluster: Arc<Cluster>, master: NodeId, session_id: S::Id, session_nonce: Option<u64>, is_exclusive_session: bool, creation_data: Option<D>) -> Result<Arc<S>, Error> {
 		let mut sessions = self.sessions.write();
 		if sessions.contains_key(&session_id) {
@@ -329,9 +465,41 @@ impl<S, SC, D> ClusterSessionsContainer<S, SC, D> where S: ClusterSession, SC: C
 	}
 
 	pub fn enqueue_message(&self, session_id: &S::Id, sender: NodeId, message: Message, is_queued_message: bool) {
-		self.sessions.write().get_mut(session_id)
-			.map(|session| if is_queued_message { session.queue.push_front((sender, message)) }
-				else { session.queue.push_back((sender, message)) });
+		let mut sessions = self.sessions.write();
+		let session = match sessions.get_mut(session_id) {
+			Some(session) => session,
+			None => return,
+		};
+
+		// do not let the queue grow unbounded, when queued messages never become processable
+		// => drop either the re-queued message, or the oldest queued message
+		if session.queue.len() >= MAX_SESSION_QUEUE_SIZE {
+			self.dropped_messages.fetch_add(1, Ordering::Relaxed);
+
+			if is_queued_message {
+				warn!(target: "secretstore_net", "message queue overflow in {} session {:?}: dropping re-queued message", S::type_name(), session_id);
+				return;
+			}
+
+			warn!(target: "secretstore_net", "message queue overflow in {} session {:?}: dropping oldest queued message", S::type_name(), session_id);
+			session.queue.pop_front();
+		}
+
+		if is_queued_message {
+			session.queue.push_front((sender, message));
+		} else {
+			session.queue.push_back((sender, message));
+		}
+	}
+
+	/// Get number of messages, dropped because of session queue overflow.
+	pub fn dropped_messages(&self) -> usize {
+		self.dropped_messages.load(Ordering::Relaxed)
+	}
+
+	#[cfg(test)]
+	pub fn queue_len(&self, session_id: &S::Id) -> Option<usize> {
+		self.sessions.read().get(session_id).map(|session| session.queue.len())
 	}
 
 	pub fn dequeue_message(&self, session_id: &S::Id) -> Option<(NodeId, Message)> {
@@ -553,13 +721,15 @@ pub fn create_cluster_view(data: &Arc<ClusterData>, requires_all_connections: bo
 
 #[cfg(test)]
 mod tests {
-	use std::sync::Arc;
-	use ethkey::{Random, Generator};
-	use key_server_cluster::{Error, DummyAclStorage, DummyKeyStorage, MapKeyServerSet, PlainNodeKeyPair};
+	use std::sync::{mpsc, Arc};
+	use std::thread;
+	use ethkey::{Random, Generator, sign};
+	use key_server_cluster::{Error, DummyAclStorage, AclStorageHandle, DummyKeyStorage, MapKeyServerSet, PlainNodeKeyPair};
 	use key_server_cluster::cluster::ClusterConfiguration;
 	use key_server_cluster::connection_trigger::SimpleServersSetChangeSessionCreatorConnector;
 	use key_server_cluster::cluster::tests::DummyCluster;
-	use super::{ClusterSessions, AdminSessionCreationData};
+	use key_server_cluster::message::{Message, ClusterMessage, KeepAlive};
+	use super::{ClusterSessions, AdminSessionCreationData, SigningSessionCreationData, SessionIdWithSubSession, SigningRequests, MAX_SESSION_QUEUE_SIZE};
 
 	pub fn make_cluster_sessions() -> ClusterSessions {
 		let key_pair = Random.generate().unwrap();
@@ -570,9 +740,10 @@ mod tests {
 			key_server_set: Arc::new(MapKeyServerSet::new(vec![(key_pair.public().clone(), format!("127.0.0.1:{}", 100).parse().unwrap())].into_iter().collect())),
 			allow_connecting_to_higher_nodes: false,
 			key_storage: Arc::new(DummyKeyStorage::default()),
-			acl_storage: Arc::new(DummyAclStorage::default()),
+			acl_storage: Arc::new(AclStorageHandle::new(Arc::new(DummyAclStorage::default()))),
 			admin_public: Some(Random.generate().unwrap().public().clone()),
 			auto_migrate_enabled: false,
+			signing_session_options: Default::default(),
 		};
 		ClusterSessions::new(&config, Arc::new(SimpleServersSetChangeSessionCreatorConnector {
 			admin_public: Some(Random.generate().unwrap().public().clone()),
@@ -601,4 +772,62 @@ mod tests {
 			Ok(_) => unreachable!("OK"),
 		}
 	}
+
+	#[test]
+	fn signing_sessions_are_filtered_by_workload() {
+		let sessions = make_cluster_sessions();
+		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
+		for workload in vec![None, Some("bridge"), None] {
+			let session_id = SessionIdWithSubSession::new(Default::default(), Random.generate().unwrap().secret().clone());
+			sessions.signing_sessions.insert(Arc::new(DummyCluster::new(Default::default())), Default::default(), session_id, None, false, Some(SigningSessionCreationData {
+				requester_signature: signature.clone(),
+				request_options: SigningRequestOptions {
+					workload: workload.map(Into::into),
+					..Default::default()
+				},
+			})).unwrap();
+		}
+
+		assert_eq!(sessions.signing_sessions.filter(|s| s.workload().is_none()).len(), 2);
+		assert_eq!(sessions.signing_sessions.filter(|s| s.workload() == Some("bridge")).len(), 1);
+	}
+
+	#[test]
+	fn session_queue_is_bounded() {
+		let sessions = make_cluster_sessions();
+		sessions.generation_sessions.insert(Arc::new(DummyCluster::new(Default::default())), Default::default(), Default::default(), None, false, None).unwrap();
+		for _ in 0..MAX_SESSION_QUEUE_SIZE + 10 {
+			sessions.generation_sessions.enqueue_message(&Default::default(), Default::default(),
+				Message::Cluster(ClusterMessage::KeepAlive(KeepAlive {})), false);
+		}
+
+		assert_eq!(sessions.generation_sessions.queue_len(&Default::default()), Some(MAX_SESSION_QUEUE_SIZE));
+		assert_eq!(sessions.generation_sessions.dropped_messages(), 10);
+	}
+
+	#[test]
+	fn signing_request_is_not_waiting_for_start_of_unrelated_request() {
+		let requests = Arc::new(SigningRequests::default());
+		let signature = sign(Random.generate().unwrap().secret(), &Default::default()).unwrap();
+		let (started_tx, started_rx) = mpsc::channel();
+		let (release_tx, release_rx) = mpsc::channel::<()>();
+
+		// start of the first request is blocked
+		let handle = {
+			let (requests, signature) = (requests.clone(), signature.clone());
+			thread::spawn(move || requests.get_or_start(Default::default(), signature, 1.into(), 777.into(), || {
+				started_tx.send(()).unwrap();
+				release_rx.recv().unwrap();
+				Err(Error::AccessDenied)
+			}))
+		};
+		started_rx.recv().unwrap();
+
+		// => unrelated request is started without waiting
+		assert_eq!(requests.get_or_start(Default::default(), signature, 2.into(), 777.into(), || Err(Error::NodeDisconnected)).err(),
+			Some(Error::NodeDisconnected));
+
+		release_tx.send(()).unwrap();
+		assert_eq!(handle.join().unwrap().err(), Some(Error::AccessDenied));
+	}
 }
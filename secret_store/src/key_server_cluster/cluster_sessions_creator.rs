@@ -19,10 +19,11 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::collections::BTreeMap;
 use parking_lot::RwLock;
 use ethkey::{Public, Signature};
-use key_server_cluster::{Error, NodeId, SessionId, AclStorage, KeyStorage, DocumentKeyShare, SessionMeta};
+use key_server_cluster::{Error, NodeId, SessionId, AclStorageHandle, KeyStorage, DocumentKeyShare, SessionMeta};
 use key_server_cluster::cluster::{Cluster, ClusterConfiguration};
 use key_server_cluster::connection_trigger::ServersSetChangeSessionCreatorConnector;
-use key_server_cluster::cluster_sessions::{ClusterSession, SessionIdWithSubSession, AdminSession, AdminSessionCreationData};
+use key_server_cluster::cluster_sessions::{ClusterSession, SessionIdWithSubSession, AdminSession, AdminSessionCreationData,
+	SigningSessionCreationData};
 use key_server_cluster::message::{self, Message, DecryptionMessage, SigningMessage, ConsensusMessageOfShareAdd,
 	ShareAddMessage, ServersSetChangeMessage, ConsensusMessage, ConsensusMessageWithServersSet};
 use key_server_cluster::generation_session::{SessionImpl as GenerationSessionImpl, SessionParams as GenerationSessionParams};
@@ -30,7 +31,7 @@ use key_server_cluster::decryption_session::{SessionImpl as DecryptionSessionImp
 	SessionParams as DecryptionSessionParams};
 use key_server_cluster::encryption_session::{SessionImpl as EncryptionSessionImpl, SessionParams as EncryptionSessionParams};
 use key_server_cluster::signing_session::{SessionImpl as SigningSessionImpl,
-	SessionParams as SigningSessionParams};
+	SessionParams as SigningSessionParams, SigningSessionOptions};
 use key_server_cluster::share_add_session::{SessionImpl as ShareAddSessionImpl,
 	SessionParams as ShareAddSessionParams, IsolatedSessionTransport as ShareAddTransport};
 use key_server_cluster::servers_set_change_session::{SessionImpl as ServersSetChangeSessionImpl,
@@ -65,8 +66,8 @@ pub struct SessionCreatorCore {
 	self_node_id: NodeId,
 	/// Reference to key storage
 	key_storage: Arc<KeyStorage>,
-	/// Reference to ACL storage
-	acl_storage: Arc<AclStorage>,
+	/// Reference to ACL storage handle. Every session captures current backend when created.
+	acl_storage: Arc<AclStorageHandle>,
 	/// Always-increasing sessions counter. Is used as session nonce to prevent replay attacks:
 	/// 1) during handshake, KeyServers generate new random key to encrypt messages
 	/// => there's no way to use messages from previous connections for replay attacks
@@ -79,17 +80,22 @@ pub struct SessionCreatorCore {
 	session_counter: AtomicUsize,
 	/// Maximal session nonce, received from given connection.
 	max_nonce: RwLock<BTreeMap<NodeId, u64>>,
+	/// Options, passed to every signing session. Consensus confirmations are signed with key pair of this node.
+	signing_session_options: SigningSessionOptions,
 }
 
 impl SessionCreatorCore {
 	/// Create new session creator core.
 	pub fn new(config: &ClusterConfiguration) -> Self {
+		let mut signing_session_options = config.signing_session_options.clone();
+		signing_session_options.confirmation_key_pair = Some(config.self_key_pair.clone());
 		SessionCreatorCore {
 			self_node_id: config.self_key_pair.public().clone(),
 			acl_storage: config.acl_storage.clone(),
 			key_storage: config.key_storage.clone(),
 			session_counter: AtomicUsize::new(0),
 			max_nonce: RwLock::new(BTreeMap::new()),
+			signing_session_options: signing_session_options,
 		}
 	}
 
@@ -233,7 +239,7 @@ impl ClusterSessionCreator<DecryptionSessionImpl, Signature> for DecryptionSessi
 			},
 			access_key: id.access_key,
 			key_share: encrypted_data,
-			acl_storage: self.core.acl_storage.clone(),
+			acl_storage: self.core.acl_storage.current(),
 			cluster: cluster,
 			nonce: nonce,
 		}, requester_signature)?))
@@ -246,16 +252,21 @@ pub struct SigningSessionCreator {
 	pub core: Arc<SessionCreatorCore>,
 }
 
-impl ClusterSessionCreator<SigningSessionImpl, Signature> for SigningSessionCreator {
-	fn creation_data_from_message(message: &Message) -> Result<Option<Signature>, Error> {
-		match *message {
+impl ClusterSessionCreator<SigningSessionImpl, SigningSessionCreationData> for SigningSessionCreator {
+	fn creation_data_from_message(message: &Message) -> Result<Option<SigningSessionCreationData>, Error> {
+		let requester_signature = match *message {
 			Message::Signing(SigningMessage::SigningConsensusMessage(ref message)) => match &message.message {
-				&ConsensusMessage::InitializeConsensusSession(ref message) => Ok(Some(message.requestor_signature.clone().into())),
-				_ => Err(Error::InvalidMessage),
+				&ConsensusMessage::InitializeConsensusSession(ref message) => message.requestor_signature.clone().into(),
+				_ => return Err(Error::InvalidMessage),
 			},
-			Message::Signing(SigningMessage::SigningSessionDelegation(ref message)) => Ok(Some(message.requestor_signature.clone().into())),
-			_ => Err(Error::InvalidMessage),
-		}
+			Message::Signing(SigningMessage::SigningSessionDelegation(ref message)) => message.requestor_signature.clone().into(),
+			_ => return Err(Error::InvalidMessage),
+		};
+
+		Ok(Some(SigningSessionCreationData {
+			requester_signature: requester_signature,
+			request_options: Default::default(),
+		}))
 	}
 
 	fn make_error_message(sid: SessionIdWithSubSession, nonce: u64, err: Error) -> Message {
@@ -267,9 +278,13 @@ impl ClusterSessionCreator<SigningSessionImpl, Signature> for SigningSessionCrea
 		}))
 	}
 
-	fn create(&self, cluster: Arc<Cluster>, master: NodeId, nonce: Option<u64>, id: SessionIdWithSubSession, requester_signature: Option<Signature>) -> Result<Arc<SigningSessionImpl>, Error> {
+	fn create(&self, cluster: Arc<Cluster>, master: NodeId, nonce: Option<u64>, id: SessionIdWithSubSession, creation_data: Option<SigningSessionCreationData>) -> Result<Arc<SigningSessionImpl>, Error> {
 		let encrypted_data = self.core.read_key_share(&id.id)?;
 		let nonce = self.core.check_session_nonce(&master, nonce)?;
+		let (requester_signature, request_options) = match creation_data {
+			Some(creation_data) => (Some(creation_data.requester_signature), creation_data.request_options),
+			None => (None, Default::default()),
+		};
 		Ok(Arc::new(SigningSessionImpl::new(SigningSessionParams {
 			meta: SessionMeta {
 				id: id.id,
@@ -279,9 +294,13 @@ impl ClusterSessionCreator<SigningSessionImpl, Signature> for SigningSessionCrea
 			},
 			access_key: id.access_key,
 			key_share: encrypted_data,
-			acl_storage: self.core.acl_storage.clone(),
+			acl_storage: self.core.acl_storage.current(),
 			cluster: cluster,
 			nonce: nonce,
+			max_lifetime: None,
+			mandatory_nodes: Default::default(),
+			request_options: request_options,
+			options: self.core.signing_session_options.clone(),
 		}, requester_signature)?))
 	}
 }
@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use parking_lot::{Mutex, RwLock};
 use ethkey::Signature;
 use key_server_cluster::{Error, NodeId, SessionMeta};
 use key_server_cluster::message::ConsensusMessage;
@@ -60,6 +63,48 @@ pub struct ConsensusSession<ConsensusExecutor: JobExecutor<PartialJobResponse=bo
 	consensus_group: BTreeSet<NodeId>,
 	/// Computation job.
 	computation_job: Option<JobSession<ComputationExecutor, ComputationTransport>>,
+	/// Partial requests of both jobs are sent in batches of this size.
+	requests_batch_size: Option<usize>,
+	/// Health of nodes, used to prefer healthier nodes when selecting consensus group.
+	node_health: Option<Arc<NodeHealthTracker>>,
+}
+
+/// Per-node success/latency history, shared by sessions of the same node.
+#[derive(Default)]
+pub struct NodeHealthTracker {
+	/// Health of every known node.
+	nodes: RwLock<BTreeMap<NodeId, NodeHealth>>,
+	/// Alert, which is fired when node is failing too often.
+	degradation_alert: Option<NodeDegradationAlert>,
+}
+
+/// Listener of node degradation alerts.
+pub trait NodeDegradationListener: Send + Sync {
+	/// Called when failure rate of the node over recent attempts has exceeded configured level.
+	fn on_node_degraded(&self, node: &NodeId);
+}
+
+/// Node degradation alert configuration && state.
+struct NodeDegradationAlert {
+	/// Number of recent attempts, failure rate is computed over.
+	window: usize,
+	/// Failure rate (per mille), after which node is considered degraded.
+	max_failures_per_mille: u64,
+	/// Alert listener.
+	listener: Arc<NodeDegradationListener>,
+	/// Recent attempts outcomes (true if failed) && degradation flag of every node.
+	recent: Mutex<BTreeMap<NodeId, (VecDeque<bool>, bool)>>,
+}
+
+/// Health history of single node.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct NodeHealth {
+	/// Number of successfully computed partial responses.
+	pub successes: u64,
+	/// Number of failures.
+	pub failures: u64,
+	/// Total latency of successfully computed partial responses.
+	pub total_latency: Duration,
 }
 
 /// Consensus session creation parameters.
@@ -90,6 +135,8 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 			consensus_job: consensus_job,
 			consensus_group: BTreeSet::new(),
 			computation_job: None,
+			requests_batch_size: None,
+			node_health: None,
 		})
 	}
 
@@ -103,6 +150,22 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 		&mut self.consensus_job
 	}
 
+	/// Send partial requests of both consensus and computation jobs in batches of given size.
+	pub fn set_requests_batch_size(&mut self, requests_batch_size: Option<usize>) {
+		self.requests_batch_size = requests_batch_size;
+		self.consensus_job.set_requests_batch_size(requests_batch_size);
+	}
+
+	/// Prefer healthier nodes when selecting consensus group.
+	pub fn set_node_health_tracker(&mut self, node_health: Arc<NodeHealthTracker>) {
+		self.node_health = Some(node_health);
+	}
+
+	/// Get health tracker of nodes.
+	pub fn node_health_tracker(&self) -> Option<&Arc<NodeHealthTracker>> {
+		self.node_health.as_ref()
+	}
+
 	/// Get all nodes, which has not rejected consensus request.
 	pub fn consensus_non_rejected_nodes(&self) -> BTreeSet<NodeId> {
 		self.consensus_job.responses().iter()
@@ -121,6 +184,21 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 			.expect("computation_job must only be called on master nodes")
 	}
 
+	/// Get nodes, which have responded to computation job requests.
+	pub fn computation_responders(&self) -> BTreeSet<NodeId> {
+		self.computation_job.as_ref().map(|job| job.responders()).unwrap_or_default()
+	}
+
+	/// Get partial responses, received by computation job. Must only be called on master node.
+	pub fn computation_responses(&self) -> Option<&BTreeMap<NodeId, ComputationExecutor::PartialJobResponse>> {
+		self.computation_job.as_ref().map(|job| job.responses())
+	}
+
+	/// Get number of partial responses, received by computation job.
+	pub fn computation_responses_count(&self) -> usize {
+		self.computation_job.as_ref().map(|job| job.responses_count()).unwrap_or_default()
+	}
+
 	/// Get consensus session state.
 	pub fn state(&self) -> ConsensusSessionState {
 		self.state
@@ -168,6 +246,12 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 		if self.consensus_group.is_empty() {
 			let consensus_group = self.consensus_job.result()?;
 			let is_self_in_consensus = consensus_group.contains(&self.meta.self_node_id);
+			let mut consensus_group: Vec<_> = consensus_group.into_iter().collect();
+			if let Some(node_health) = self.node_health.as_ref() {
+				// master is always preferred, other nodes are ordered by their health
+				let master_node_id = &self.meta.master_node_id;
+				consensus_group.sort_by_key(|node| (node != master_node_id, node_health.rank(node)));
+			}
 			self.consensus_group = consensus_group.into_iter().take(self.meta.threshold + 1).collect();
 
 			if is_self_in_consensus {
@@ -185,6 +269,7 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 		self.consensus_group.clear();
 
 		let mut computation_job = JobSession::new(self.meta.clone(), executor, transport);
+		computation_job.set_requests_batch_size(self.requests_batch_size);
 		let computation_result = computation_job.initialize(consensus_group);
 		self.computation_job = Some(computation_job);
 		self.state = ConsensusSessionState::WaitingForPartialResults;
@@ -238,13 +323,13 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 				// it is strange to receive error before session is initialized && slave doesn't know access_key
 				// => fatal error
 				self.state = ConsensusSessionState::Failed;
-				(false, Err(Error::ConsensusUnreachable))
+				(false, Err(Error::ConsensusUnreachable { retry_after: None }))
 			}
 			ConsensusSessionState::WaitingForInitialization if is_node_master => {
 				// can not establish consensus
 				// => fatal error
 				self.state = ConsensusSessionState::Failed;
-				(false, Err(Error::ConsensusUnreachable))
+				(false, Err(Error::ConsensusUnreachable { retry_after: None }))
 			},
 			ConsensusSessionState::EstablishingConsensus => {
 				debug_assert!(is_self_master);
@@ -296,7 +381,7 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 
 				self.consensus_group.clear();
 				self.state = ConsensusSessionState::EstablishingConsensus;
-				return self.process_result(Err(Error::ConsensusUnreachable)).map(|_| unreachable!());
+				return self.process_result(Err(Error::ConsensusUnreachable { retry_after: None })).map(|_| unreachable!());
 			},
 			// in all other cases - just ignore error
 			ConsensusSessionState::Finished | ConsensusSessionState::Failed => return Ok(false),
@@ -358,15 +443,101 @@ impl<ConsensusExecutor, ConsensusTransport, ComputationExecutor, ComputationTran
 	}
 }
 
+impl NodeHealthTracker {
+	/// Create tracker, which alerts listener when node fails more than `max_failures_per_mille` of its
+	/// last `window` attempts. Alert is fired once, when node becomes degraded.
+	pub fn with_degradation_alert(window: usize, max_failures_per_mille: u64, listener: Arc<NodeDegradationListener>) -> Self {
+		NodeHealthTracker {
+			nodes: Default::default(),
+			degradation_alert: Some(NodeDegradationAlert {
+				window: ::std::cmp::max(window, 1),
+				max_failures_per_mille: max_failures_per_mille,
+				listener: listener,
+				recent: Default::default(),
+			}),
+		}
+	}
+
+	/// Record successfully computed partial response.
+	pub fn record_success(&self, node: &NodeId, latency: Duration) {
+		{
+			let mut nodes = self.nodes.write();
+			let health = nodes.entry(node.clone()).or_insert_with(Default::default);
+			health.successes += 1;
+			health.total_latency += latency;
+		}
+		self.record_outcome(node, false);
+	}
+
+	/// Record node failure.
+	pub fn record_failure(&self, node: &NodeId) {
+		self.nodes.write().entry(node.clone()).or_insert_with(Default::default).failures += 1;
+		self.record_outcome(node, true);
+	}
+
+	/// Update recent outcomes of the node && fire degradation alert if required.
+	fn record_outcome(&self, node: &NodeId, is_failure: bool) {
+		let alert = match self.degradation_alert.as_ref() {
+			Some(alert) => alert,
+			None => return,
+		};
+
+		let is_newly_degraded = {
+			let mut recent = alert.recent.lock();
+			let &mut (ref mut outcomes, ref mut is_degraded) = recent.entry(node.clone()).or_insert_with(Default::default);
+			outcomes.push_back(is_failure);
+			if outcomes.len() > alert.window {
+				outcomes.pop_front();
+			}
+
+			// failure rate is only computed over full window => single failure of new node doesn't raise an alert
+			let failures = outcomes.iter().filter(|is_failure| **is_failure).count() as u64;
+			let was_degraded = *is_degraded;
+			*is_degraded = outcomes.len() == alert.window && failures * 1_000 / alert.window as u64 > alert.max_failures_per_mille;
+			*is_degraded && !was_degraded
+		};
+
+		// listener is called without holding the lock
+		if is_newly_degraded {
+			alert.listener.on_node_degraded(node);
+		}
+	}
+
+	/// Get health history of given node.
+	pub fn health(&self, node: &NodeId) -> NodeHealth {
+		self.nodes.read().get(node).cloned().unwrap_or_default()
+	}
+
+	/// Get rank of the node: (failures per mille, average latency in milliseconds). Lower is healthier.
+	/// Nodes without history are considered healthy.
+	fn rank(&self, node: &NodeId) -> (u64, u64) {
+		let health = self.health(node);
+		let attempts = health.successes + health.failures;
+		if attempts == 0 {
+			return (0, 0);
+		}
+
+		let failures_per_mille = health.failures * 1_000 / attempts;
+		let average_latency = match health.successes {
+			0 => 0,
+			successes => (health.total_latency.as_secs() * 1_000 + (health.total_latency.subsec_nanos() / 1_000_000) as u64) / successes,
+		};
+		(failures_per_mille, average_latency)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use std::sync::Arc;
+	use std::collections::BTreeSet;
+	use std::time::Duration;
 	use ethkey::{Signature, KeyPair, Random, Generator, sign};
 	use key_server_cluster::{Error, NodeId, SessionId, DummyAclStorage};
 	use key_server_cluster::message::{ConsensusMessage, InitializeConsensusSession, ConfirmConsensusInitialization};
 	use key_server_cluster::jobs::job_session::tests::{make_master_session_meta, make_slave_session_meta, SquaredSumJobExecutor, DummyJobTransport};
 	use key_server_cluster::jobs::key_access_job::KeyAccessJob;
-	use super::{ConsensusSession, ConsensusSessionParams, ConsensusSessionState};
+	use parking_lot::Mutex;
+	use super::{ConsensusSession, ConsensusSessionParams, ConsensusSessionState, NodeHealthTracker, NodeDegradationListener};
 
 	type SquaredSumConsensusSession = ConsensusSession<KeyAccessJob, DummyJobTransport<Signature, bool>, SquaredSumJobExecutor, DummyJobTransport<u32, u32>>;
 
@@ -394,6 +565,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::EstablishingConsensus);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 	}
@@ -405,6 +577,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 	}
@@ -420,6 +593,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::EstablishingConsensus);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 	}
@@ -431,7 +605,7 @@ mod tests {
 		acl_storage.prohibit(requester.public().clone(), SessionId::default());
 
 		let mut session = make_master_consensus_session(1, Some(requester), Some(acl_storage));
-		assert_eq!(session.initialize(vec![NodeId::from(1), NodeId::from(2)].into_iter().collect()).unwrap_err(), Error::ConsensusUnreachable);
+		assert_eq!(session.initialize(vec![NodeId::from(1), NodeId::from(2)].into_iter().collect()).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
@@ -442,7 +616,8 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::EstablishingConsensus);
 		assert_eq!(session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: false,
-		})).unwrap_err(), Error::ConsensusUnreachable);
+			signature: None,
+		})).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
@@ -459,6 +634,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
@@ -477,6 +653,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::EstablishingConsensus);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
@@ -503,6 +680,7 @@ mod tests {
 		session.on_consensus_message(&NodeId::from(1), &ConsensusMessage::InitializeConsensusSession(InitializeConsensusSession {
 			requestor_signature: sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap().into(),
 			version: Default::default(),
+			deadline: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		assert_eq!(session.on_job_request(&NodeId::from(1), 20, SquaredSumJobExecutor, DummyJobTransport::default()).unwrap_err(), Error::InvalidMessage);
@@ -516,6 +694,7 @@ mod tests {
 		session.on_consensus_message(&NodeId::from(1), &ConsensusMessage::InitializeConsensusSession(InitializeConsensusSession {
 			requestor_signature: sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap().into(),
 			version: Default::default(),
+			deadline: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		session.on_job_request(&NodeId::from(1), 2, SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
@@ -546,6 +725,7 @@ mod tests {
 		session.on_consensus_message(&NodeId::from(1), &ConsensusMessage::InitializeConsensusSession(InitializeConsensusSession {
 			requestor_signature: sign(Random.generate().unwrap().secret(), &SessionId::default()).unwrap().into(),
 			version: Default::default(),
+			deadline: None,
 		})).unwrap();
 		session.on_session_completed(&NodeId::from(1)).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::Finished);
@@ -554,14 +734,14 @@ mod tests {
 	#[test]
 	fn consensus_session_fails_if_node_error_received_by_uninitialized_master() {
 		let mut session = make_master_consensus_session(0, None, None);
-		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable { retry_after: None }));
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
 	#[test]
 	fn consensus_session_fails_if_node_error_received_by_uninitialized_slave_from_master() {
 		let mut session = make_slave_consensus_session(0, None);
-		assert_eq!(session.on_node_error(&NodeId::from(1)), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_node_error(&NodeId::from(1)), Err(Error::ConsensusUnreachable { retry_after: None }));
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
@@ -576,7 +756,7 @@ mod tests {
 	fn consensus_session_fails_if_node_error_received_by_master_during_establish_and_not_enough_nodes_left() {
 		let mut session = make_master_consensus_session(1, None, None);
 		session.initialize(vec![NodeId::from(1), NodeId::from(2)].into_iter().collect()).unwrap();
-		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable { retry_after: None }));
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
@@ -586,6 +766,7 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.on_node_error(&NodeId::from(2)), Ok(false));
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
@@ -597,6 +778,7 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.on_node_error(&NodeId::from(3)), Ok(false));
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
@@ -608,8 +790,9 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
-		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable { retry_after: None }));
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
@@ -619,9 +802,11 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3), NodeId::from(4)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		session.on_consensus_message(&NodeId::from(3), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
 		assert_eq!(session.on_node_error(&NodeId::from(3)), Ok(false));
@@ -635,12 +820,14 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3), NodeId::from(4)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::WaitingForPartialResults);
 
 		session.on_consensus_message(&NodeId::from(3), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.on_node_error(&NodeId::from(2)), Ok(true));
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
@@ -657,16 +844,17 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
-		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_node_error(&NodeId::from(2)), Err(Error::ConsensusUnreachable { retry_after: None }));
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
 	#[test]
 	fn consensus_session_fails_if_uninitialized_session_timeouts() {
 		let mut session = make_master_consensus_session(1, None, None);
-		assert_eq!(session.on_session_timeout(), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_session_timeout(), Err(Error::ConsensusUnreachable { retry_after: None }));
 	}
 
 	#[test]
@@ -675,6 +863,7 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3), NodeId::from(4)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
@@ -682,6 +871,7 @@ mod tests {
 
 		session.on_consensus_message(&NodeId::from(3), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.on_session_timeout(), Ok(true));
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
@@ -699,11 +889,12 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::WaitingForPartialResults);
 
-		assert_eq!(session.on_session_timeout(), Err(Error::ConsensusUnreachable));
+		assert_eq!(session.on_session_timeout(), Err(Error::ConsensusUnreachable { retry_after: None }));
 		assert_eq!(session.state(), ConsensusSessionState::Failed);
 	}
 
@@ -713,9 +904,11 @@ mod tests {
 		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3)].into_iter().collect()).unwrap();
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		session.on_consensus_message(&NodeId::from(3), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 
 		let consensus_group1 = session.select_consensus_group().unwrap().clone();
@@ -723,6 +916,76 @@ mod tests {
 		assert_eq!(consensus_group1, consensus_group2);
 	}
 
+	#[test]
+	fn unhealthy_node_is_deprioritized_in_consensus_group_selection() {
+		let node_health = Arc::new(NodeHealthTracker::default());
+		node_health.record_success(&NodeId::from(2), Duration::from_millis(10));
+		node_health.record_failure(&NodeId::from(2));
+		node_health.record_failure(&NodeId::from(2));
+		node_health.record_success(&NodeId::from(3), Duration::from_millis(10));
+
+		let mut session = make_master_consensus_session(1, None, None);
+		session.set_node_health_tracker(node_health);
+		session.initialize(vec![NodeId::from(1), NodeId::from(2), NodeId::from(3)].into_iter().collect()).unwrap();
+		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
+			is_confirmed: true,
+			signature: None,
+		})).unwrap();
+		session.on_consensus_message(&NodeId::from(3), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
+			is_confirmed: true,
+			signature: None,
+		})).unwrap();
+
+		// node 2 has confirmed, but it is less healthy than node 3
+		let expected_group: BTreeSet<_> = vec![NodeId::from(1), NodeId::from(3)].into_iter().collect();
+		assert_eq!(session.select_consensus_group().unwrap(), &expected_group);
+	}
+
+	#[derive(Default)]
+	struct DummyNodeDegradationListener {
+		degraded: Mutex<Vec<NodeId>>,
+	}
+
+	impl NodeDegradationListener for DummyNodeDegradationListener {
+		fn on_node_degraded(&self, node: &NodeId) {
+			self.degraded.lock().push(node.clone());
+		}
+	}
+
+	#[test]
+	fn node_degradation_alert_is_fired_when_failure_rate_crosses_threshold() {
+		let listener = Arc::new(DummyNodeDegradationListener::default());
+		let node_health = NodeHealthTracker::with_degradation_alert(4, 500, listener.clone());
+
+		// healthy node is never reported
+		for _ in 0..10 {
+			node_health.record_success(&NodeId::from(3), Duration::from_millis(10));
+		}
+
+		// failure rate is not computed until window is full
+		node_health.record_success(&NodeId::from(2), Duration::from_millis(10));
+		node_health.record_failure(&NodeId::from(2));
+		node_health.record_failure(&NodeId::from(2));
+		assert!(listener.degraded.lock().is_empty());
+
+		// 3 of 4 last attempts have failed => alert
+		node_health.record_failure(&NodeId::from(2));
+		assert_eq!(*listener.degraded.lock(), vec![NodeId::from(2)]);
+
+		// alert is fired once while node stays degraded
+		node_health.record_failure(&NodeId::from(2));
+		assert_eq!(*listener.degraded.lock(), vec![NodeId::from(2)]);
+
+		// node recovers && degrades again => alert is fired again
+		for _ in 0..4 {
+			node_health.record_success(&NodeId::from(2), Duration::from_millis(10));
+		}
+		for _ in 0..3 {
+			node_health.record_failure(&NodeId::from(2));
+		}
+		assert_eq!(*listener.degraded.lock(), vec![NodeId::from(2), NodeId::from(2)]);
+	}
+
 	#[test]
 	fn consensus_session_complete_2_of_4() {
 		let mut session = make_master_consensus_session(1, None, None);
@@ -730,6 +993,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::EstablishingConsensus);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 		session.disseminate_jobs(SquaredSumJobExecutor, DummyJobTransport::default()).unwrap();
@@ -746,6 +1010,7 @@ mod tests {
 		assert_eq!(session.state(), ConsensusSessionState::EstablishingConsensus);
 		session.on_consensus_message(&NodeId::from(2), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 
@@ -754,6 +1019,7 @@ mod tests {
 
 		session.on_consensus_message(&NodeId::from(3), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 
 		assert_eq!(session.on_node_error(&NodeId::from(2)).unwrap(), true);
@@ -767,6 +1033,7 @@ mod tests {
 
 		session.on_consensus_message(&NodeId::from(4), &ConsensusMessage::ConfirmConsensusInitialization(ConfirmConsensusInitialization {
 			is_confirmed: true,
+			signature: None,
 		})).unwrap();
 		assert_eq!(session.state(), ConsensusSessionState::ConsensusEstablished);
 
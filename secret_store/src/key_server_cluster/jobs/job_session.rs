@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::collections::{BTreeSet, BTreeMap};
+use std::collections::{BTreeSet, BTreeMap, VecDeque};
 use key_server_cluster::{Error, NodeId, SessionMeta};
 
 /// Partial response action.
@@ -85,6 +85,8 @@ pub struct JobSession<Executor: JobExecutor, Transport> where Transport: JobTran
 	executor: Executor,
 	/// Jobs transport.
 	transport: Transport,
+	/// Max number of partial requests, which are waiting for response. All requests are sent at once if None.
+	requests_batch_size: Option<usize>,
 	/// Session data.
 	data: JobSessionData<Executor::PartialJobResponse>,
 }
@@ -99,8 +101,12 @@ struct JobSessionData<PartialJobResponse> {
 
 /// Active job session data.
 struct ActiveJobSessionData<PartialJobResponse> {
+	/// All nodes, participating in the job.
+	nodes: BTreeSet<NodeId>,
 	/// Active partial requests.
 	requests: BTreeSet<NodeId>,
+	/// Active partial requests, which are not yet sent (because of requests batching).
+	unsent_requests: VecDeque<NodeId>,
 	/// Rejects to partial requests.
 	rejects: BTreeSet<NodeId>,
 	/// Received partial responses.
@@ -114,6 +120,7 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 			meta: meta,
 			executor: executor,
 			transport: transport,
+			requests_batch_size: None,
 			data: JobSessionData {
 				state: JobSessionState::Inactive,
 				active_data: None,
@@ -121,6 +128,12 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 		}
 	}
 
+	/// Send partial requests in batches of given size. Next request is sent when response to one of
+	/// previous requests is received (or node fails), so that at most given number of requests is waiting for response.
+	pub fn set_requests_batch_size(&mut self, requests_batch_size: Option<usize>) {
+		self.requests_batch_size = requests_batch_size;
+	}
+
 	/// Get transport reference.
 	#[cfg(test)]
 	pub fn transport(&self) -> &Transport {
@@ -148,7 +161,6 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 	}
 
 	/// Get rejects.
-	#[cfg(test)]
 	pub fn rejects(&self) -> &BTreeSet<NodeId> {
 		debug_assert!(self.meta.self_node_id == self.meta.master_node_id);
 
@@ -175,6 +187,16 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 			.responses
 	}
 
+	/// Get nodes, which have responded to partial requests. Always empty on slave nodes.
+	pub fn responders(&self) -> BTreeSet<NodeId> {
+		self.data.active_data.as_ref().map(|data| data.responses.keys().cloned().collect()).unwrap_or_default()
+	}
+
+	/// Get number of received responses. Always zero on slave nodes.
+	pub fn responses_count(&self) -> usize {
+		self.data.active_data.as_ref().map(|data| data.responses.len()).unwrap_or_default()
+	}
+
 	/// Get job result.
 	pub fn result(&self) -> Result<Executor::JobResponse, Error> {
 		debug_assert!(self.meta.self_node_id == self.meta.master_node_id);
@@ -193,7 +215,7 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 		debug_assert!(self.meta.self_node_id == self.meta.master_node_id);
 
 		if nodes.len() < self.meta.threshold + 1 {
-			return Err(Error::ConsensusUnreachable);
+			return Err(Error::ConsensusUnreachable { retry_after: None });
 		}
 
 		if self.data.state != JobSessionState::Inactive {
@@ -202,7 +224,9 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 
 		// result from self
 		let active_data = ActiveJobSessionData {
+			nodes: nodes.clone(),
 			requests: nodes.clone(),
+			unsent_requests: nodes.iter().filter(|n| **n != self.meta.self_node_id).cloned().collect(),
 			rejects: BTreeSet::new(),
 			responses: BTreeMap::new(),
 		};
@@ -227,14 +251,8 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 			}
 		}
 
-		// send requests to save nodes. we only send requests if session is still active.
-		if self.data.state == JobSessionState::Active {
-			for node in nodes.iter().filter(|n| **n != self.meta.self_node_id) {
-				self.transport.send_partial_request(node, self.executor.prepare_partial_request(node, &nodes)?)?;
-			}
-		}
-
-		Ok(())
+		// send requests to save nodes
+		self.send_unsent_requests()
 	}
 
 	/// When partial request is received by slave node.
@@ -271,33 +289,33 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 			return Err(Error::InvalidStateForRequest);
 		}
 
-		let active_data = self.data.active_data.as_mut()
-			.expect("on_partial_response is only called on master nodes; on master nodes active_data is filled during initialization; qed");
-		if !active_data.requests.remove(node) {
-			return Err(Error::InvalidNodeForRequest);
-		}
-		
-		match self.executor.check_partial_response(node, &response)? {
-			JobPartialResponseAction::Ignore => Ok(()),
-			JobPartialResponseAction::Reject => {
-				active_data.rejects.insert(node.clone());
-				if active_data.requests.len() + active_data.responses.len() >= self.meta.threshold + 1 {
-					return Ok(());
-				}
-
-				self.data.state = JobSessionState::Failed;
-				Err(Error::ConsensusUnreachable)
-			},
-			JobPartialResponseAction::Accept => {
-				active_data.responses.insert(node.clone(), response);
-				if active_data.responses.len() < self.meta.threshold + 1 {
-					return Ok(());
-				}
+		{
+			let active_data = self.data.active_data.as_mut()
+				.expect("on_partial_response is only called on master nodes; on master nodes active_data is filled during initialization; qed");
+			if active_data.unsent_requests.contains(node) || !active_data.requests.remove(node) {
+				return Err(Error::InvalidNodeForRequest);
+			}
 
-				self.data.state = JobSessionState::Finished;
-				Ok(())
-			},
+			match self.executor.check_partial_response(node, &response)? {
+				JobPartialResponseAction::Ignore => (),
+				JobPartialResponseAction::Reject => {
+					active_data.rejects.insert(node.clone());
+					if active_data.requests.len() + active_data.responses.len() < self.meta.threshold + 1 {
+						self.data.state = JobSessionState::Failed;
+						return Err(Error::ConsensusUnreachable { retry_after: None });
+					}
+				},
+				JobPartialResponseAction::Accept => {
+					active_data.responses.insert(node.clone(), response);
+					if active_data.responses.len() >= self.meta.threshold + 1 {
+						self.data.state = JobSessionState::Finished;
+					}
+				},
+			}
 		}
+
+		// request has been completed => send next request (if requests are batched)
+		self.send_unsent_requests()
 	}
 
 	/// When error from node is received.
@@ -308,28 +326,34 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 			}
 
 			self.data.state = JobSessionState::Failed;
-			return Err(Error::ConsensusUnreachable);
+			return Err(Error::ConsensusUnreachable { retry_after: None });
 		}
 
-		if let Some(active_data) = self.data.active_data.as_mut() {
+		{
+			let active_data = match self.data.active_data.as_mut() {
+				Some(active_data) => active_data,
+				None => return Ok(()),
+			};
 			if active_data.rejects.contains(node) {
 				return Ok(());
 			}
-			if active_data.requests.remove(node) || active_data.responses.remove(node).is_some() {
-				active_data.rejects.insert(node.clone());
-				if self.data.state == JobSessionState::Finished && active_data.responses.len() < self.meta.threshold + 1 {
-					self.data.state = JobSessionState::Active;
-				}
-				if active_data.requests.len() + active_data.responses.len() >= self.meta.threshold + 1 {
-					return Ok(());
-				}
+			if !active_data.requests.remove(node) && active_data.responses.remove(node).is_none() {
+				return Ok(());
+			}
 
+			active_data.unsent_requests.retain(|n| n != node);
+			active_data.rejects.insert(node.clone());
+			if self.data.state == JobSessionState::Finished && active_data.responses.len() < self.meta.threshold + 1 {
+				self.data.state = JobSessionState::Active;
+			}
+			if active_data.requests.len() + active_data.responses.len() < self.meta.threshold + 1 {
 				self.data.state = JobSessionState::Failed;
-				return Err(Error::ConsensusUnreachable);
+				return Err(Error::ConsensusUnreachable { retry_after: None });
 			}
 		}
 
-		Ok(())
+		// request has been completed => send next request (if requests are batched)
+		self.send_unsent_requests()
 	}
 
 	/// When session timeouted.
@@ -339,7 +363,31 @@ impl<Executor, Transport> JobSession<Executor, Transport> where Executor: JobExe
 		}
 
 		self.data.state = JobSessionState::Failed;
-		Err(Error::ConsensusUnreachable)
+		Err(Error::ConsensusUnreachable { retry_after: None })
+	}
+
+	/// Send unsent partial requests, so that at most `requests_batch_size` requests are waiting for response.
+	fn send_unsent_requests(&mut self) -> Result<(), Error> {
+		// we only send requests if session is still active
+		if self.data.state != JobSessionState::Active {
+			return Ok(());
+		}
+
+		let active_data = match self.data.active_data.as_mut() {
+			Some(active_data) => active_data,
+			None => return Ok(()),
+		};
+		let requests_batch_size = self.requests_batch_size.map(|size| ::std::cmp::max(size, 1)).unwrap_or(usize::max_value());
+		while active_data.requests.len() - active_data.unsent_requests.len() < requests_batch_size {
+			let node = match active_data.unsent_requests.pop_front() {
+				Some(node) => node,
+				None => break,
+			};
+
+			self.transport.send_partial_request(&node, self.executor.prepare_partial_request(&node, &active_data.nodes)?)?;
+		}
+
+		Ok(())
 	}
 }
 
@@ -471,7 +519,7 @@ pub mod tests {
 		let mut job = JobSession::new(make_master_session_meta(1), SquaredSumJobExecutor, DummyJobTransport::default());
 		job.initialize(vec![Public::from(1), Public::from(2)].into_iter().collect()).unwrap();
 		assert_eq!(job.state(), JobSessionState::Active);
-		assert_eq!(job.on_partial_response(&NodeId::from(2), 3).unwrap_err(), Error::ConsensusUnreachable);
+		assert_eq!(job.on_partial_response(&NodeId::from(2), 3).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 		assert_eq!(job.state(), JobSessionState::Failed);
 	}
 
@@ -505,7 +553,7 @@ pub mod tests {
 	fn job_node_error_leads_to_fail_when_slave_disconnects_from_master() {
 		let mut job = JobSession::new(make_slave_session_meta(1), SquaredSumJobExecutor, DummyJobTransport::default());
 		assert_eq!(job.state(), JobSessionState::Inactive);
-		assert_eq!(job.on_node_error(&NodeId::from(1)).unwrap_err(), Error::ConsensusUnreachable);
+		assert_eq!(job.on_node_error(&NodeId::from(1)).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 		assert_eq!(job.state(), JobSessionState::Failed);
 	}
 
@@ -542,7 +590,32 @@ pub mod tests {
 		let mut job = JobSession::new(make_master_session_meta(1), SquaredSumJobExecutor, DummyJobTransport::default());
 		job.initialize(vec![Public::from(1), Public::from(2)].into_iter().collect()).unwrap();
 		assert_eq!(job.state(), JobSessionState::Active);
-		assert_eq!(job.on_node_error(&NodeId::from(2)).unwrap_err(), Error::ConsensusUnreachable);
+		assert_eq!(job.on_node_error(&NodeId::from(2)).unwrap_err(), Error::ConsensusUnreachable { retry_after: None });
 		assert_eq!(job.state(), JobSessionState::Failed);
 	}
+
+	#[test]
+	fn job_requests_are_sent_in_batches() {
+		let mut job = JobSession::new(make_master_session_meta(3), SquaredSumJobExecutor, DummyJobTransport::default());
+		job.set_requests_batch_size(Some(2));
+		job.initialize(vec![Public::from(1), Public::from(2), Public::from(3), Public::from(4), Public::from(5)].into_iter().collect()).unwrap();
+		assert_eq!(job.transport().requests.lock().len(), 2);
+
+		// next request is sent when response is received
+		job.on_partial_response(&NodeId::from(2), 4).unwrap();
+		assert_eq!(job.transport().requests.lock().len(), 3);
+
+		// ...or when node fails
+		job.on_node_error(&NodeId::from(3)).unwrap();
+		assert_eq!(job.transport().requests.lock().len(), 4);
+
+		// response from node, which hasn't been requested yet, is rejected
+		assert_eq!(job.on_partial_response(&NodeId::from(6), 4).unwrap_err(), Error::InvalidNodeForRequest);
+
+		job.on_partial_response(&NodeId::from(4), 4).unwrap();
+		job.on_partial_response(&NodeId::from(5), 4).unwrap();
+		assert_eq!(job.state(), JobSessionState::Finished);
+		assert_eq!(job.transport().requests.lock().iter().map(|&(ref node, _)| node.clone()).collect::<Vec<_>>(),
+			vec![NodeId::from(2), NodeId::from(3), NodeId::from(4), NodeId::from(5)]);
+	}
 }
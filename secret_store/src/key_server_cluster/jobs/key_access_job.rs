@@ -15,7 +15,10 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::sync::Arc;
-use std::collections::{BTreeSet, BTreeMap};
+use std::collections::{BTreeSet, BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use ethereum_types::H256;
 use ethkey::{Public, Signature, recover};
 use key_server_cluster::{Error, NodeId, SessionId, AclStorage};
 use key_server_cluster::jobs::job_session::{JobPartialResponseAction, JobPartialRequestAction, JobExecutor};
@@ -30,6 +33,18 @@ pub struct KeyAccessJob {
 	acl_storage: Arc<AclStorage>,
 	/// Requester signature.
 	signature: Option<Signature>,
+	/// Key version.
+	version: Option<H256>,
+	/// Cache of ACL decisions.
+	decisions_cache: Option<Arc<AccessDecisionsCache>>,
+}
+
+/// Cache of ACL decisions, keyed by requester, key id and key version.
+pub struct AccessDecisionsCache {
+	/// Time, for which decision is cached. ACL changes are respected after this time.
+	ttl: Duration,
+	/// Cached decisions.
+	decisions: Mutex<HashMap<(Public, SessionId, H256), (bool, Instant)>>,
 }
 
 impl KeyAccessJob {
@@ -39,6 +54,8 @@ impl KeyAccessJob {
 			has_key_share: true,
 			acl_storage: acl_storage,
 			signature: None,
+			version: None,
+			decisions_cache: None,
 		}
 	}
 
@@ -48,6 +65,8 @@ impl KeyAccessJob {
 			has_key_share: true,
 			acl_storage: acl_storage,
 			signature: Some(signature),
+			version: None,
+			decisions_cache: None,
 		}
 	}
 
@@ -59,16 +78,64 @@ impl KeyAccessJob {
 		self.signature = Some(signature);
 	}
 
+	pub fn set_version(&mut self, version: H256) {
+		self.version = Some(version);
+	}
+
+	pub fn set_decisions_cache(&mut self, decisions_cache: Arc<AccessDecisionsCache>) {
+		self.decisions_cache = Some(decisions_cache);
+	}
+
 	pub fn requester_signature(&self) -> Option<&Signature> {
 		self.signature.as_ref()
 	}
 
 	pub fn requester(&self) -> Result<Option<Public>, Error> {
 		match self.signature.as_ref() {
-			Some(signature) => Ok(Some(recover(signature, &self.id)?)),
+			Some(signature) => Ok(Some(recover_requester(signature, &self.id)?)),
 			None => Ok(None),
 		}
 	}
+
+	/// Check if requester has access to the key, according to ACL of this node.
+	pub fn check_requester_access(&self) -> Result<bool, Error> {
+		let requester = self.requester()?.ok_or(Error::InvalidStateForRequest)?;
+		self.acl_storage.check(&requester, &self.id).map_err(|_| Error::AccessDenied)
+	}
+}
+
+/// Recover requester public from its signature. Malformed signature (including one, recovering to zero public) is
+/// rejected as invalid message, so that it is never confused with valid requester, which has no access to the key.
+fn recover_requester(signature: &Signature, id: &SessionId) -> Result<Public, Error> {
+	let requester = recover(signature, id).map_err(|_| Error::InvalidMessage)?;
+	if requester.is_zero() {
+		return Err(Error::InvalidMessage);
+	}
+
+	Ok(requester)
+}
+
+impl AccessDecisionsCache {
+	/// Create new cache with given decision lifetime.
+	pub fn new(ttl: Duration) -> Self {
+		AccessDecisionsCache {
+			ttl: ttl,
+			decisions: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Get cached decision.
+	pub fn get(&self, requester: &Public, key_id: &SessionId, version: &H256) -> Option<bool> {
+		let mut decisions = self.decisions.lock();
+		let ttl = self.ttl;
+		decisions.retain(|_, &mut (_, ref cached)| cached.elapsed() < ttl);
+		decisions.get(&(requester.clone(), key_id.clone(), version.clone())).map(|&(is_confirmed, _)| is_confirmed)
+	}
+
+	/// Cache decision.
+	pub fn insert(&self, requester: Public, key_id: SessionId, version: H256, is_confirmed: bool) {
+		self.decisions.lock().insert((requester, key_id, version), (is_confirmed, Instant::now()));
+	}
 }
 
 impl JobExecutor for KeyAccessJob {
@@ -86,9 +153,23 @@ impl JobExecutor for KeyAccessJob {
 		}
 		
 		self.signature = Some(partial_request.clone());
-		self.acl_storage.check(&recover(&partial_request, &self.id)?, &self.id)
-			.map_err(|_| Error::AccessDenied)
-			.map(|is_confirmed| if is_confirmed { JobPartialRequestAction::Respond(true) } else { JobPartialRequestAction::Reject(false) })
+		let requester = recover_requester(&partial_request, &self.id)?;
+		let decisions_cache = match (self.decisions_cache.as_ref(), self.version.as_ref()) {
+			(Some(decisions_cache), Some(version)) => Some((decisions_cache, version)),
+			_ => None,
+		};
+		let is_confirmed = match decisions_cache.and_then(|(cache, version)| cache.get(&requester, &self.id, version)) {
+			Some(is_confirmed) => is_confirmed,
+			None => {
+				let is_confirmed = self.acl_storage.check(&requester, &self.id).map_err(|_| Error::AccessDenied)?;
+				if let Some((cache, version)) = decisions_cache {
+					cache.insert(requester, self.id.clone(), version.clone(), is_confirmed);
+				}
+				is_confirmed
+			},
+		};
+
+		Ok(if is_confirmed { JobPartialRequestAction::Respond(true) } else { JobPartialRequestAction::Reject(false) })
 	}
 
 	fn check_partial_response(&mut self, _sender: &NodeId, partial_response: &bool) -> Result<JobPartialResponseAction, Error> {
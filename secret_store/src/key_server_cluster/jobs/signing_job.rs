@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::sync::Arc;
 use std::collections::{BTreeSet, BTreeMap};
 use ethkey::{Public, Secret};
 use ethereum_types::H256;
-use key_server_cluster::{Error, NodeId, DocumentKeyShare};
-use key_server_cluster::math;
+use key_server_cluster::{Error, NodeId, DocumentKeyShare, DocumentKeyShareVersion};
+use key_server_cluster::math::{self, MathBackend, DefaultMathBackend};
 use key_server_cluster::jobs::job_session::{JobPartialRequestAction, JobPartialResponseAction, JobExecutor};
 
 /// Signing job.
@@ -27,8 +28,8 @@ pub struct SigningJob {
 	self_node_id: NodeId,
 	/// Key share.
 	key_share: DocumentKeyShare,
-	/// Key version.
-	key_version: H256,
+	/// Key version, pinned for the whole job lifetime.
+	key_version: DocumentKeyShareVersion,
 	/// Session public key.
 	session_public: Public,
 	/// Session secret coefficient.
@@ -37,6 +38,10 @@ pub struct SigningJob {
 	request_id: Option<Secret>,
 	/// Message hash.
 	message_hash: Option<H256>,
+	/// Blind secret share when computing partial signature.
+	partial_signature_blinding: bool,
+	/// Backend of signing computations.
+	math_backend: Arc<MathBackend>,
 }
 
 /// Signing job partial request.
@@ -58,7 +63,7 @@ pub struct PartialSigningResponse {
 }
 
 impl SigningJob {
-	pub fn new_on_slave(self_node_id: NodeId, key_share: DocumentKeyShare, key_version: H256, session_public: Public, session_secret_coeff: Secret) -> Result<Self, Error> {
+	pub fn new_on_slave(self_node_id: NodeId, key_share: DocumentKeyShare, key_version: DocumentKeyShareVersion, session_public: Public, session_secret_coeff: Secret) -> Result<Self, Error> {
 		Ok(SigningJob {
 			self_node_id: self_node_id,
 			key_share: key_share,
@@ -67,10 +72,12 @@ impl SigningJob {
 			session_secret_coeff: session_secret_coeff,
 			request_id: None,
 			message_hash: None,
+			partial_signature_blinding: false,
+			math_backend: Arc::new(DefaultMathBackend),
 		})
 	}
 
-	pub fn new_on_master(self_node_id: NodeId, key_share: DocumentKeyShare, key_version: H256, session_public: Public, session_secret_coeff: Secret, message_hash: H256) -> Result<Self, Error> {
+	pub fn new_on_master(self_node_id: NodeId, key_share: DocumentKeyShare, key_version: DocumentKeyShareVersion, session_public: Public, session_secret_coeff: Secret, message_hash: H256) -> Result<Self, Error> {
 		Ok(SigningJob {
 			self_node_id: self_node_id,
 			key_share: key_share,
@@ -79,8 +86,18 @@ impl SigningJob {
 			session_secret_coeff: session_secret_coeff,
 			request_id: Some(math::generate_random_scalar()?),
 			message_hash: Some(message_hash),
+			partial_signature_blinding: false,
+			math_backend: Arc::new(DefaultMathBackend),
 		})
 	}
+
+	pub fn set_partial_signature_blinding(&mut self, partial_signature_blinding: bool) {
+		self.partial_signature_blinding = partial_signature_blinding;
+	}
+
+	pub fn set_math_backend(&mut self, math_backend: Arc<MathBackend>) {
+		self.math_backend = math_backend;
+	}
 }
 
 impl JobExecutor for SigningJob {
@@ -106,7 +123,7 @@ impl JobExecutor for SigningJob {
 	}
 
 	fn process_partial_request(&mut self, partial_request: PartialSigningRequest) -> Result<JobPartialRequestAction<PartialSigningResponse>, Error> {
-		let key_version = self.key_share.version(&self.key_version).map_err(|e| Error::KeyStorage(e.into()))?;
+		let key_version = &self.key_version;
 		if partial_request.other_nodes_ids.len() != self.key_share.threshold
 			|| partial_request.other_nodes_ids.contains(&self.self_node_id)
 			|| partial_request.other_nodes_ids.iter().any(|n| !key_version.id_numbers.contains_key(n)) {
@@ -114,18 +131,18 @@ impl JobExecutor for SigningJob {
 		}
 
 		let self_id_number = &key_version.id_numbers[&self.self_node_id];
-		let other_id_numbers = partial_request.other_nodes_ids.iter().map(|n| &key_version.id_numbers[n]);
-		let combined_hash = math::combine_message_hash_with_public(&partial_request.message_hash, &self.session_public)?;
+		let other_id_numbers: Vec<_> = partial_request.other_nodes_ids.iter().map(|n| &key_version.id_numbers[n]).collect();
+		let combined_hash = self.math_backend.combine_message_hash_with_public(&partial_request.message_hash, &self.session_public)?;
+		let partial_signature = if self.partial_signature_blinding {
+			self.math_backend.compute_blinded_signature_share(self.key_share.threshold, &combined_hash, &self.session_secret_coeff,
+				&key_version.secret_share, self_id_number, &other_id_numbers)?
+		} else {
+			self.math_backend.compute_signature_share(self.key_share.threshold, &combined_hash, &self.session_secret_coeff,
+				&key_version.secret_share, self_id_number, &other_id_numbers)?
+		};
 		Ok(JobPartialRequestAction::Respond(PartialSigningResponse {
 			request_id: partial_request.id,
-			partial_signature: math::compute_signature_share(
-				self.key_share.threshold,
-				&combined_hash,
-				&self.session_secret_coeff,
-				&key_version.secret_share,
-				self_id_number,
-				other_id_numbers
-			)?,
+			partial_signature: partial_signature,
 		}))
 	}
 
@@ -142,8 +159,9 @@ impl JobExecutor for SigningJob {
 		let message_hash = self.message_hash.as_ref()
 			.expect("compute_response is only called on master nodes; message_hash is filed in constructor on master nodes; qed");
 
-		let signature_c = math::combine_message_hash_with_public(message_hash, &self.session_public)?;
-		let signature_s = math::compute_signature(partial_responses.values().map(|r| &r.partial_signature))?;
+		let signature_c = self.math_backend.combine_message_hash_with_public(message_hash, &self.session_public)?;
+		let partial_signatures: Vec<_> = partial_responses.values().map(|r| &r.partial_signature).collect();
+		let signature_s = self.math_backend.compute_signature(&partial_signatures)?;
 
 		Ok((signature_c, signature_s))
 	}
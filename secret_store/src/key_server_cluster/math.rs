@@ -19,6 +19,24 @@ use ethereum_types::{H256, U256};
 use hash::keccak;
 use key_server_cluster::Error;
 
+/// Backend of signing computations, allowing to replace default implementation (i.e. with hardware-accelerated one).
+pub trait MathBackend: Send + Sync {
+	/// Combine message hash with public key X coordinate.
+	fn combine_message_hash_with_public(&self, message_hash: &H256, public: &Public) -> Result<Secret, Error>;
+	/// Compute signature share.
+	fn compute_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+		node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error>;
+	/// Compute signature share, using randomly blinded secret share.
+	fn compute_blinded_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+		node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error>;
+	/// Compute signature from signature shares.
+	fn compute_signature(&self, signature_shares: &[&Secret]) -> Result<Secret, Error>;
+}
+
+/// Math backend, which is using functions of this module.
+#[derive(Default)]
+pub struct DefaultMathBackend;
+
 /// Encryption result.
 #[derive(Debug)]
 pub struct EncryptedSecret {
@@ -364,6 +382,41 @@ pub fn compute_signature_share<'a, I>(threshold: usize, combined_hash: &Secret,
 	Ok(sum)
 }
 
+/// Compute signature share, using randomly blinded secret share. Result is equal to the result of compute_signature_share.
+pub fn compute_blinded_signature_share<'a, I>(threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret, node_number: &Secret, other_nodes_numbers: I)
+	-> Result<Secret, Error> where I: Iterator<Item=&'a Secret> {
+	let other_nodes_numbers: Vec<_> = other_nodes_numbers.collect();
+
+	// share is never used in computations directly: (s + b) is used instead
+	let blinding = generate_random_scalar()?;
+	let mut blinded_node_secret_share = node_secret_share.clone();
+	blinded_node_secret_share.add(&blinding)?;
+	let mut signature_share = compute_signature_share(threshold, combined_hash, one_time_secret_coeff, &blinded_node_secret_share,
+		node_number, other_nodes_numbers.iter().cloned())?;
+
+	// remove blinding from the result: (k -+ c * lagrange_coeff * (s + b)) +- c * lagrange_coeff * b = k -+ c * lagrange_coeff * s
+	let mut correction = compute_shadow_mul(combined_hash, node_number, other_nodes_numbers.iter().cloned())?;
+	correction.mul(&blinding)?;
+	if threshold % 2 == 0 {
+		signature_share.add(&correction)?;
+	} else {
+		signature_share.sub(&correction)?;
+	}
+	Ok(signature_share)
+}
+
+/// Compute Lagrange coefficient of node, which is implicitly applied to the node secret share in `compute_signature_share`:
+/// joint secret = sum(coeff(i) * share(i)) for every signer i.
+pub fn compute_lagrange_coefficient<'a, I>(threshold: usize, node_number: &Secret, other_nodes_numbers: I) -> Result<Secret, Error> where I: Iterator<Item=&'a Secret> {
+	let one: H256 = U256::one().into();
+	let mut coeff = compute_shadow_mul(&Secret::from_slice(&*one), node_number, other_nodes_numbers)?;
+	// shadow is using (s[i] - s[j]) instead of (s[j] - s[i]) => it must be negated when there's odd number of other nodes
+	if threshold % 2 != 0 {
+		coeff.neg()?;
+	}
+	Ok(coeff)
+}
+
 /// Check signature share.
 pub fn _check_signature_share<'a, I>(_combined_hash: &Secret, _signature_share: &Secret, _public_share: &Public, _one_time_public_share: &Public, _node_numbers: I)
 	-> Result<bool, Error> where I: Iterator<Item=&'a Secret> {
@@ -389,6 +442,26 @@ pub fn compute_signature<'a, I>(signature_shares: I) -> Result<Secret, Error> wh
 	compute_secret_sum(signature_shares)
 }
 
+impl MathBackend for DefaultMathBackend {
+	fn combine_message_hash_with_public(&self, message_hash: &H256, public: &Public) -> Result<Secret, Error> {
+		combine_message_hash_with_public(message_hash, public)
+	}
+
+	fn compute_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+		node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error> {
+		compute_signature_share(threshold, combined_hash, one_time_secret_coeff, node_secret_share, node_number, other_nodes_numbers.iter().cloned())
+	}
+
+	fn compute_blinded_signature_share(&self, threshold: usize, combined_hash: &Secret, one_time_secret_coeff: &Secret, node_secret_share: &Secret,
+		node_number: &Secret, other_nodes_numbers: &[&Secret]) -> Result<Secret, Error> {
+		compute_blinded_signature_share(threshold, combined_hash, one_time_secret_coeff, node_secret_share, node_number, other_nodes_numbers.iter().cloned())
+	}
+
+	fn compute_signature(&self, signature_shares: &[&Secret]) -> Result<Secret, Error> {
+		compute_signature(signature_shares.iter().cloned())
+	}
+}
+
 /// Locally compute Schnorr signature as described in https://en.wikipedia.org/wiki/Schnorr_signature#Signing.
 #[cfg(test)]
 pub fn local_compute_signature(nonce: &Secret, secret: &Secret, message_hash: &Secret) -> Result<(Secret, Secret), Error> {
@@ -681,6 +754,41 @@ pub mod tests {
 		}
 	}
 
+	#[test]
+	fn blinded_signature_shares_produce_the_same_signature() {
+		let test_cases = [(0, 1), (1, 2), (1, 3), (2, 5), (3, 5)];
+		for &(t, n) in &test_cases {
+			let message_hash: Secret = "0000000000000000000000000000000000000000000000000000000000000042".parse().unwrap();
+			let artifacts = run_key_generation(t, n, None);
+			let n = t + 1;
+			let id_numbers = artifacts.id_numbers.iter().cloned().take(n).collect();
+			let one_time_artifacts = run_key_generation(t, n, Some(id_numbers));
+			let combined_hash = combine_message_hash_with_public(&message_hash, &one_time_artifacts.joint_public).unwrap();
+
+			let compute_shares = |blinded: bool| -> Vec<Secret> {
+				(0..n).map(|i| {
+					let other_nodes_numbers = artifacts.id_numbers.iter()
+						.enumerate()
+						.filter(|&(j, _)| i != j)
+						.map(|(_, n)| n)
+						.take(t);
+					if blinded {
+						compute_blinded_signature_share(t, &combined_hash, &one_time_artifacts.polynoms1[i][0],
+							&artifacts.secret_shares[i], &artifacts.id_numbers[i], other_nodes_numbers).unwrap()
+					} else {
+						compute_signature_share(t, &combined_hash, &one_time_artifacts.polynoms1[i][0],
+							&artifacts.secret_shares[i], &artifacts.id_numbers[i], other_nodes_numbers).unwrap()
+					}
+				}).collect()
+			};
+
+			let signature = (combined_hash.clone(), compute_signature(compute_shares(false).iter()).unwrap());
+			let blinded_signature = (combined_hash.clone(), compute_signature(compute_shares(true).iter()).unwrap());
+			assert_eq!(signature, blinded_signature);
+			assert_eq!(verify_signature(&artifacts.joint_public, &blinded_signature, &message_hash), Ok(true));
+		}
+	}
+
 	#[test]
 	fn full_generation_math_session_with_refreshing_shares() {
 		let test_cases = vec![(1, 4), (6, 10)];
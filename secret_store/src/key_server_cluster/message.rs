@@ -152,6 +152,8 @@ pub enum SigningMessage {
 	SigningSessionDelegation(SigningSessionDelegation),
 	/// When delegated signing session is completed.
 	SigningSessionDelegationCompleted(SigningSessionDelegationCompleted),
+	/// When delegated signing session is cancelled by delegating node.
+	SigningSessionDelegationCancel(SigningSessionDelegationCancel),
 }
 
 /// All possible messages that can be sent during servers set change session.
@@ -366,6 +368,9 @@ pub struct InitializeConsensusSession {
 	pub requestor_signature: SerializableSignature,
 	/// Key version.
 	pub version: SerializableH256,
+	/// Absolute session deadline (milliseconds since UNIX epoch). Nodes, which can't meet the deadline, decline participation.
+	#[serde(default)]
+	pub deadline: Option<u64>,
 }
 
 /// Node is responding to consensus initialization request.
@@ -373,6 +378,9 @@ pub struct InitializeConsensusSession {
 pub struct ConfirmConsensusInitialization {
 	/// Is node confirmed consensus participation.
 	pub is_confirmed: bool,
+	/// Signature of confirming node over session id and decision (if any).
+	#[serde(default)]
+	pub signature: Option<SerializableSignature>,
 }
 
 /// Node is asked to be part of servers-set consensus group.
@@ -519,6 +527,20 @@ pub struct SigningSessionDelegationCompleted {
 	pub signature_s: SerializableSecret,
 	/// C-portion of signature.
 	pub signature_c: SerializableSecret,
+	/// Hash of signed message (missing in messages from older nodes).
+	#[serde(default)]
+	pub message_hash: Option<SerializableH256>,
+}
+
+/// When delegated signing session is cancelled by delegating node.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SigningSessionDelegationCancel {
+	/// Encryption session Id.
+	pub session: MessageSessionId,
+	/// Signing session Id.
+	pub sub_session: SerializableSecret,
+	/// Session-level nonce.
+	pub session_nonce: u64,
 }
 
 /// Consensus-related decryption message.
@@ -1007,6 +1029,7 @@ impl SigningMessage {
 			SigningMessage::SigningSessionCompleted(ref msg) => &msg.session,
 			SigningMessage::SigningSessionDelegation(ref msg) => &msg.session,
 			SigningMessage::SigningSessionDelegationCompleted(ref msg) => &msg.session,
+			SigningMessage::SigningSessionDelegationCancel(ref msg) => &msg.session,
 		}
 	}
 
@@ -1020,6 +1043,7 @@ impl SigningMessage {
 			SigningMessage::SigningSessionCompleted(ref msg) => &msg.sub_session,
 			SigningMessage::SigningSessionDelegation(ref msg) => &msg.sub_session,
 			SigningMessage::SigningSessionDelegationCompleted(ref msg) => &msg.sub_session,
+			SigningMessage::SigningSessionDelegationCancel(ref msg) => &msg.sub_session,
 		}
 	}
 
@@ -1033,6 +1057,7 @@ impl SigningMessage {
 			SigningMessage::SigningSessionCompleted(ref msg) => msg.session_nonce,
 			SigningMessage::SigningSessionDelegation(ref msg) => msg.session_nonce,
 			SigningMessage::SigningSessionDelegationCompleted(ref msg) => msg.session_nonce,
+			SigningMessage::SigningSessionDelegationCancel(ref msg) => msg.session_nonce,
 		}
 	}
 }
@@ -1219,6 +1244,7 @@ impl fmt::Display for SigningMessage {
 			SigningMessage::SigningSessionCompleted(_) => write!(f, "SigningSessionCompleted"),
 			SigningMessage::SigningSessionDelegation(_) => write!(f, "SigningSessionDelegation"),
 			SigningMessage::SigningSessionDelegationCompleted(_) => write!(f, "SigningSessionDelegationCompleted"),
+			SigningMessage::SigningSessionDelegationCancel(_) => write!(f, "SigningSessionDelegationCancel"),
 		}
 	}
 }
@@ -16,13 +16,14 @@
 
 use std::fmt;
 use std::io::Error as IoError;
+use std::time::Duration;
 use ethkey;
 use ethcrypto;
 use super::types::all::ServerKeyId;
 
 pub use super::traits::NodeKeyPair;
 pub use super::types::all::{NodeId, EncryptedDocumentKeyShadow};
-pub use super::acl_storage::AclStorage;
+pub use super::acl_storage::{AclStorage, AclStorageHandle};
 pub use super::key_storage::{KeyStorage, DocumentKeyShare, DocumentKeyShareVersion};
 pub use super::key_server_set::{is_migration_required, KeyServerSet, KeyServerSetSnapshot, KeyServerSetMigration};
 pub use super::serialization::{SerializableSignature, SerializableH256, SerializableSecret, SerializablePublic, SerializableMessageHash};
@@ -106,13 +107,24 @@ pub enum Error {
 	/// Key storage error.
 	KeyStorage(String),
 	/// Consensus is unreachable.
-	ConsensusUnreachable,
+	ConsensusUnreachable {
+		/// Suggested delay before retrying the request.
+		retry_after: Option<Duration>,
+	},
 	/// Acl storage error.
 	AccessDenied,
 	/// Can't start session, because exclusive session is active.
 	ExclusiveSessionActive,
 	/// Can't start exclusive session, because there are other active sessions.
 	HasActiveSessions,
+	/// Session has not been completed in time.
+	SessionTimeout,
+	/// Session has been cancelled.
+	Cancelled,
+	/// Node is in maintenance mode and doesn't accept new sessions.
+	NodeInMaintenance,
+	/// Session parameters are inconsistent with each other or with the key share.
+	InconsistentSessionParams,
 }
 
 impl From<ethkey::Error> for Error {
@@ -157,10 +169,15 @@ impl fmt::Display for Error {
 			Error::Io(ref e) => write!(f, "i/o error {}", e),
 			Error::Serde(ref e) => write!(f, "serde error {}", e),
 			Error::KeyStorage(ref e) => write!(f, "key storage error {}", e),
-			Error::ConsensusUnreachable => write!(f, "Consensus unreachable"),
+			Error::ConsensusUnreachable { retry_after: None } => write!(f, "Consensus unreachable"),
+			Error::ConsensusUnreachable { retry_after: Some(ref retry_after) } => write!(f, "Consensus unreachable, retry after {:?}", retry_after),
 			Error::AccessDenied => write!(f, "Access denied"),
 			Error::ExclusiveSessionActive => write!(f, "Exclusive session active"),
 			Error::HasActiveSessions => write!(f, "Unable to start exclusive session"),
+			Error::SessionTimeout => write!(f, "Session has not been completed in time"),
+			Error::Cancelled => write!(f, "Session has been cancelled"),
+			Error::NodeInMaintenance => write!(f, "Node is in maintenance mode"),
+			Error::InconsistentSessionParams => write!(f, "Session parameters are inconsistent"),
 		}
 	}
 }
@@ -62,12 +62,14 @@ mod listener;
 mod trusted_client;
 
 use std::sync::Arc;
+use std::time::Duration;
 use ethcore::client::Client;
 use ethsync::SyncProvider;
 
 pub use types::all::{ServerKeyId, EncryptedDocumentKey, RequestSignature, Public,
 	Error, NodeAddress, ContractAddress, ServiceConfiguration, ClusterConfiguration};
 pub use traits::{NodeKeyPair, KeyServer};
+pub use acl_storage::{AclStorage, AclStorageHandle};
 pub use self::node_key_pair::{PlainNodeKeyPair, KeyStoreNodeKeyPair};
 
 /// Start new key server instance
@@ -82,6 +84,7 @@ pub fn start(client: Arc<Client>, sync: Arc<SyncProvider>, self_key_pair: Arc<No
 	let key_server_set = key_server_set::OnChainKeyServerSet::new(trusted_client.clone(), self_key_pair.clone(),
 		config.cluster_config.auto_migrate_enabled, config.cluster_config.nodes.clone())?;
 	let key_storage = Arc::new(key_storage::PersistentKeyStorage::new(&config)?);
+	let acl_storage = Arc::new(acl_storage::AclStorageHandle::new(acl_storage));
 	let key_server = Arc::new(key_server::KeyServerImpl::new(&config.cluster_config, key_server_set.clone(), self_key_pair.clone(), acl_storage, key_storage.clone())?);
 	let cluster = key_server.cluster();
 
@@ -105,3 +108,8 @@ pub fn start(client: Arc<Client>, sync: Arc<SyncProvider>, self_key_pair: Arc<No
 	});
 	Ok(Box::new(listener::Listener::new(key_server, http_listener, contract_listener)))
 }
+
+/// Estimate signing throughput of this node (in signatures per second), using throwaway keys.
+pub fn benchmark_signing_throughput(duration: Duration) -> Result<f64, Error> {
+	key_server_cluster::signing_session::benchmark::benchmark_signing_throughput(duration).map_err(Into::into)
+}
@@ -24,6 +24,7 @@ use std::sync::Arc;
 use traits::{ServerKeyGenerator, DocumentKeyServer, MessageSigner, AdminSessionsServer, KeyServer};
 use types::all::{Error, Public, MessageHash, EncryptedMessageSignature, RequestSignature, ServerKeyId,
 	EncryptedDocumentKey, EncryptedDocumentKeyShadow, NodeId};
+use acl_storage::AclStorageHandle;
 
 pub struct Listener {
 	key_server: Arc<KeyServer>,
@@ -41,7 +42,11 @@ impl Listener {
 	}
 }
 
-impl KeyServer for Listener {}
+impl KeyServer for Listener {
+	fn acl_storage(&self) -> Arc<AclStorageHandle> {
+		self.key_server.acl_storage()
+	}
+}
 
 impl ServerKeyGenerator for Listener {
 	fn generate_key(&self, key_id: &ServerKeyId, signature: &RequestSignature, threshold: usize) -> Result<Public, Error> {
@@ -15,10 +15,12 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::collections::BTreeSet;
+use std::sync::Arc;
 use ethkey::{KeyPair, Signature, Error as EthKeyError};
 use ethereum_types::{H256, Address};
 use types::all::{Error, Public, ServerKeyId, MessageHash, EncryptedMessageSignature, RequestSignature, EncryptedDocumentKey,
 	EncryptedDocumentKeyShadow, NodeId};
+use acl_storage::AclStorageHandle;
 
 /// Node key pair.
 pub trait NodeKeyPair: Send + Sync {
@@ -95,4 +97,6 @@ pub trait AdminSessionsServer {
 
 /// Key server.
 pub trait KeyServer: AdminSessionsServer + DocumentKeyServer + MessageSigner + Send + Sync {
+	/// Get ACL storage handle, which could be used to swap ACL storage at runtime.
+	fn acl_storage(&self) -> Arc<AclStorageHandle>;
 }